@@ -143,6 +143,7 @@ impl ActivityContract {
             content: params.content,
             cover: "".to_string(),
             abbreviation: "".to_string(),
+            publish_at: None,
         };
         let review_app_id = self.review_app_id();
         self.runtime.call_application(true, review_app_id, &call);
@@ -173,7 +174,9 @@ impl ActivityContract {
         let call = review::Operation::SubmitActivity {
             activity_id,
             activity_host: owner,
+            host_chain_id: self.require_message_id()?.chain_id,
             budget_amount: params.budget_amount,
+            sponsors: params.sponsors,
         };
         let review_app_id = self.review_app_id();
         let _ = self.runtime.call_application(true, review_app_id, &call);
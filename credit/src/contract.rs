@@ -3,7 +3,7 @@
 mod state;
 
 use self::state::Credit;
-use credit::{CreditAbi, CreditError, InstantiationArgument, Message, Operation};
+use credit::{CreditAbi, CreditError, CreditResponse, InstantiationArgument, Message, Operation};
 use linera_sdk::{
     base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
     views::{RootView, View},
@@ -49,9 +49,12 @@ impl Contract for CreditContract {
             Operation::SetTransferCallers { application_ids } => self
                 .on_op_set_transfer_callers(application_ids)
                 .expect("Failed OP: set transfer callers"),
-            Operation::Transfer { from, to, amount } => self
-                .on_op_transfer(from, to, amount)
-                .expect("Failed OP: transfer"),
+            Operation::Transfer { from, to, amount } => {
+                return self
+                    .on_op_transfer(from, to, amount)
+                    .await
+                    .expect("Failed OP: transfer")
+            }
             Operation::TransferExt { to, amount } => self
                 .on_op_transfer_ext(to, amount)
                 .expect("Failed OP: transfer from application"),
@@ -61,7 +64,11 @@ impl Contract for CreditContract {
             Operation::Reward { owner, amount } => {
                 self.on_op_reward(owner, amount).expect("Failed OP: reward")
             }
+            Operation::Balance { owner } => {
+                return self.on_op_balance(owner).await.expect("Failed OP: balance")
+            }
         }
+        CreditResponse::Ok
     }
 
     async fn execute_message(&mut self, message: Message) {
@@ -121,6 +128,10 @@ impl CreditContract {
         }
     }
 
+    async fn on_op_balance(&mut self, owner: Owner) -> Result<CreditResponse, CreditError> {
+        Ok(CreditResponse::Balance(self.state.spendable(owner).await?))
+    }
+
     fn on_op_liquidate(&mut self) -> Result<(), CreditError> {
         self.runtime
             .prepare_message(Message::Liquidate)
@@ -154,17 +165,21 @@ impl CreditContract {
         Ok(())
     }
 
-    fn on_op_transfer(
+    async fn on_op_transfer(
         &mut self,
         from: Owner,
         to: Owner,
         amount: Amount,
-    ) -> Result<(), CreditError> {
+    ) -> Result<CreditResponse, CreditError> {
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            self.on_msg_transfer(from, to, amount).await?;
+            return Ok(CreditResponse::Ok);
+        }
         self.runtime
             .prepare_message(Message::Transfer { from, to, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(())
+        Ok(CreditResponse::Pending)
     }
 
     fn on_op_transfer_ext(&mut self, to: Owner, amount: Amount) -> Result<(), CreditError> {
@@ -10,7 +10,7 @@ pub struct CreditAbi;
 
 impl ContractAbi for CreditAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = CreditResponse;
 }
 
 impl ServiceAbi for CreditAbi {
@@ -39,6 +39,17 @@ impl AgeAmounts {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub enum CreditResponse {
+    #[default]
+    Ok,
+    Balance(Amount),
+    /// Transfer called off creation chain is sent there and not applied yet. Applications which
+    /// pay with credits in the same transaction, like market, must be created on the creation
+    /// chain of credit, otherwise every payment fails
+    Pending,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct InstantiationArgument {
     pub initial_supply: Amount,
@@ -68,6 +79,9 @@ pub enum Operation {
         owner: Owner,
         amount: Amount,
     },
+    Balance {
+        owner: Owner,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -50,6 +50,11 @@ impl Credit {
         }
     }
 
+    /// Credits could be transferred by owner
+    pub(crate) async fn spendable(&self, owner: Owner) -> Result<Amount, CreditError> {
+        Ok(self.spendables.get(&owner).await?.unwrap_or(Amount::ZERO))
+    }
+
     pub(crate) async fn reward(
         &mut self,
         owner: Owner,
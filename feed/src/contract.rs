@@ -7,11 +7,15 @@ use std::collections::HashMap;
 use self::state::Feed;
 use credit::CreditAbi;
 use feed::{
-    Content, FeedError, FeedParameters, FeedResponse, InstantiationArgument, Message, Operation,
+    Content, Draft, FeedError, FeedParameters, FeedResponse, InstantiationArgument, Message,
+    Operation,
 };
 use foundation::FoundationAbi;
 use linera_sdk::{
-    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{
+        Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, Timestamp,
+        WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
@@ -28,6 +32,51 @@ impl WithContractAbi for FeedContract {
 }
 
 const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
+/// Scheduled contents released by one ReleaseScheduled message
+const MAX_RELEASE_BATCH: usize = 20;
+
+/// Content to publish, recommendations and comments are published under another content
+#[derive(Clone)]
+struct Publication {
+    cid: String,
+    comment_to_cid: Option<String>,
+    title: String,
+    content: String,
+    cover: String,
+    abbreviation: String,
+    author: Owner,
+    publish_at: Option<Timestamp>,
+    review_score: Option<u16>,
+}
+
+impl Publication {
+    fn reply(cid: String, comment_to_cid: String, content: String, author: Owner) -> Self {
+        Publication {
+            cid,
+            comment_to_cid: Some(comment_to_cid),
+            title: String::default(),
+            content,
+            cover: "".to_string(),
+            abbreviation: "".to_string(),
+            author,
+            publish_at: None,
+            review_score: None,
+        }
+    }
+
+    fn message(self) -> Message {
+        Message::Publish {
+            cid: self.cid,
+            title: self.title,
+            content: self.content,
+            cover: self.cover,
+            abbreviation: self.abbreviation,
+            author: self.author,
+            publish_at: self.publish_at,
+            review_score: self.review_score,
+        }
+    }
+}
 
 impl Contract for FeedContract {
     type Message = Message;
@@ -76,13 +125,49 @@ impl Contract for FeedContract {
                 author,
                 cover,
                 abbreviation,
+                publish_at,
+                review_score,
             } => self
-                .on_op_publish(cid, title, content, author, cover, abbreviation)
+                .on_op_publish(Publication {
+                    cid,
+                    comment_to_cid: None,
+                    title,
+                    content,
+                    cover,
+                    abbreviation,
+                    author,
+                    publish_at,
+                    review_score,
+                })
                 .expect("Failed OP: publish"),
             Operation::ContentAuthor { cid } => self
                 .on_op_content_author(cid)
                 .await
                 .expect("Failed OP: content author"),
+            Operation::ReleaseScheduled => self
+                .on_op_release_scheduled()
+                .expect("Failed OP: release scheduled"),
+            Operation::SaveDraft {
+                cid,
+                title,
+                content,
+                cover,
+                abbreviation,
+            } => self
+                .on_op_save_draft(cid, title, content, cover, abbreviation)
+                .await
+                .expect("Failed OP: save draft"),
+            Operation::DeleteDraft { cid } => self
+                .on_op_delete_draft(cid)
+                .await
+                .expect("Failed OP: delete draft"),
+            Operation::SubmitDraft { cid } => self
+                .on_op_submit_draft(cid)
+                .await
+                .expect("Failed OP: submit draft"),
+            Operation::SetDraftCallers { application_ids } => self
+                .on_op_set_draft_callers(application_ids)
+                .expect("Failed OP: set draft callers"),
         }
     }
 
@@ -102,8 +187,20 @@ impl Contract for FeedContract {
                 author,
                 cover,
                 abbreviation,
+                publish_at,
+                review_score,
             } => self
-                .on_msg_publish(cid, title, content, author, cover, abbreviation)
+                .on_msg_publish(Publication {
+                    cid,
+                    comment_to_cid: None,
+                    title,
+                    content,
+                    cover,
+                    abbreviation,
+                    author,
+                    publish_at,
+                    review_score,
+                })
                 .await
                 .expect("Failed MSG: publish"),
             Message::Recommend {
@@ -125,7 +222,20 @@ impl Contract for FeedContract {
                 .expect("Failed MSG: comment"),
             Message::RequestSubscribe => self
                 .on_msg_request_subscribe()
+                .await
                 .expect("Failed MSG: subscribe"),
+            Message::ReleaseScheduled => self
+                .on_msg_release_scheduled()
+                .await
+                .expect("Failed MSG: release scheduled"),
+            Message::SetDraftCallers { application_ids } => self
+                .on_msg_set_draft_callers(application_ids)
+                .await
+                .expect("Failed MSG: set draft callers"),
+            Message::ScheduledReleased { cids } => self
+                .on_msg_scheduled_released(cids)
+                .await
+                .expect("Failed MSG: scheduled released"),
         }
     }
 
@@ -165,35 +275,30 @@ impl FeedContract {
 
     async fn publish(
         &mut self,
-        cid: String,
-        comment_to_cid: Option<String>,
-        title: String,
-        content: String,
-        author: Owner,
-        cover: String,
-        abbreviation: String,
+        publication: Publication,
         creation_chain: bool,
     ) -> Result<(), FeedError> {
-        match self
-            .state
-            .create_content(
-                Content {
-                    cid,
-                    comment_to_cid,
-                    title,
-                    content,
-                    cover,
-                    abbreviation,
-                    author,
-                    likes: 0,
-                    dislikes: 0,
-                    accounts: HashMap::default(),
-                    created_at: self.runtime.system_time(),
-                },
-                author,
-            )
-            .await
-        {
+        let author = publication.author;
+        let content = Content {
+            cid: publication.cid,
+            comment_to_cid: publication.comment_to_cid,
+            title: publication.title,
+            content: publication.content,
+            cover: publication.cover,
+            abbreviation: publication.abbreviation,
+            author,
+            likes: 0,
+            dislikes: 0,
+            accounts: HashMap::default(),
+            created_at: self.runtime.system_time(),
+            publish_at: publication.publish_at,
+            review_score: publication.review_score,
+        };
+        if content.publish_at.is_some() {
+            // Author is rewarded when the content is released
+            return self.state.schedule_content(content).await;
+        }
+        match self.state.create_content(content, author).await {
             Ok(_) => {
                 if !creation_chain {
                     return Ok(());
@@ -331,24 +436,77 @@ impl FeedContract {
         Ok(FeedResponse::Ok)
     }
 
-    fn on_op_publish(
+    fn on_op_publish(&mut self, publication: Publication) -> Result<FeedResponse, FeedError> {
+        self.runtime
+            .prepare_message(publication.message())
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
+    fn on_op_release_scheduled(&mut self) -> Result<FeedResponse, FeedError> {
+        self.runtime
+            .prepare_message(Message::ReleaseScheduled)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FeedResponse::Ok)
+    }
+
+    async fn on_op_save_draft(
         &mut self,
         cid: String,
         title: String,
         content: String,
-        author: Owner,
         cover: String,
         abbreviation: String,
     ) -> Result<FeedResponse, FeedError> {
-        self.runtime
-            .prepare_message(Message::Publish {
+        let author = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        self.state
+            .save_draft(Draft {
                 cid,
+                author,
                 title,
                 content,
                 cover,
                 abbreviation,
-                author,
+                created_at: now,
+                updated_at: now,
             })
+            .await?;
+        Ok(FeedResponse::Ok)
+    }
+
+    async fn on_op_delete_draft(&mut self, cid: String) -> Result<FeedResponse, FeedError> {
+        let author = self.require_authenticated_signer()?;
+        self.state.delete_draft(author, cid).await?;
+        Ok(FeedResponse::Ok)
+    }
+
+    async fn on_op_submit_draft(&mut self, cid: String) -> Result<FeedResponse, FeedError> {
+        // Only review application could take the draft, it'll submit the draft for review.
+        // Feed can't depend on review, so the review application id is registered at runtime
+        let Some(caller_id) = self.runtime.authenticated_caller_id() else {
+            return Err(FeedError::InvalidCaller);
+        };
+        if !self.state.is_draft_caller(caller_id).await? {
+            return Err(FeedError::InvalidCaller);
+        }
+        // Drafts are keyed by author, so signer could only take its own draft
+        let author = self.require_authenticated_signer()?;
+        let draft = self.state.delete_draft(author, cid).await?;
+        Ok(FeedResponse::Draft(draft))
+    }
+
+    fn on_op_set_draft_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<FeedResponse, FeedError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetDraftCallers { application_ids })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(FeedResponse::Ok)
@@ -406,41 +564,23 @@ impl FeedContract {
         Ok(())
     }
 
-    async fn on_msg_publish(
-        &mut self,
-        cid: String,
-        title: String,
-        content: String,
-        author: Owner,
-        cover: String,
-        abbreviation: String,
-    ) -> Result<(), FeedError> {
+    async fn on_msg_publish(&mut self, mut publication: Publication) -> Result<(), FeedError> {
         let creation_chain =
             self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
-        self.publish(
-            cid.clone(),
-            None,
-            title.clone(),
-            content.clone(),
-            author,
-            cover.clone(),
-            abbreviation.clone(),
-            creation_chain,
-        )
-        .await?;
+        // Creation chain decides whether the content is scheduled, subscribers follow it
+        if creation_chain {
+            let now = self.runtime.system_time();
+            publication.publish_at = publication
+                .publish_at
+                .filter(|publish_at| *publish_at > now);
+        }
+        self.publish(publication.clone(), creation_chain).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::Publish {
-                cid,
-                title,
-                content,
-                cover,
-                abbreviation,
-                author,
-            })
+            .prepare_message(publication.message())
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -456,13 +596,7 @@ impl FeedContract {
         let creation_chain =
             self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self.publish(
-            reason_cid.clone(),
-            Some(cid.clone()),
-            String::default(),
-            reason.clone(),
-            author,
-            "".to_string(),
-            "".to_string(),
+            Publication::reply(reason_cid.clone(), cid.clone(), reason.clone(), author),
             creation_chain,
         )
         .await?;
@@ -494,13 +628,7 @@ impl FeedContract {
         let creation_chain =
             self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
         self.publish(
-            comment_cid.clone(),
-            Some(cid.clone()),
-            String::default(),
-            comment.clone(),
-            commentor,
-            "".to_string(),
-            "".to_string(),
+            Publication::reply(comment_cid.clone(), cid.clone(), comment.clone(), commentor),
             creation_chain,
         )
         .await?;
@@ -523,7 +651,59 @@ impl FeedContract {
         Ok(())
     }
 
-    fn on_msg_request_subscribe(&mut self) -> Result<(), FeedError> {
+    async fn on_msg_set_draft_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), FeedError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        self.state.set_draft_callers(application_ids.clone()).await;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetDraftCallers { application_ids })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_release_scheduled(&mut self) -> Result<(), FeedError> {
+        // Due time is only checked on creation chain, subscribers release the same cids
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let cids = self
+            .state
+            .due_scheduled_contents(self.runtime.system_time(), MAX_RELEASE_BATCH);
+        if cids.is_empty() {
+            return Ok(());
+        }
+        let contents = self.state.release_scheduled_contents(cids.clone()).await?;
+        for content in contents {
+            self.reward_credits(content.author, Amount::from_tokens(500))
+                .await?;
+            self.reward_tokens(content.author).await?;
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ScheduledReleased { cids })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_scheduled_released(&mut self, cids: Vec<String>) -> Result<(), FeedError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FeedError::OperationNotAllowed);
+        }
+        self.state.release_scheduled_contents(cids).await?;
+        Ok(())
+    }
+
+    async fn on_msg_request_subscribe(&mut self) -> Result<(), FeedError> {
         let message_id = self.require_message_id()?;
         // The subscribe message must be from another chain
         if message_id.chain_id == self.runtime.application_id().creation.chain_id {
@@ -533,6 +713,13 @@ impl FeedContract {
             message_id.chain_id,
             ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()),
         );
+        // Callers set before the chain subscribed are never broadcast to it again
+        self.runtime
+            .prepare_message(Message::SetDraftCallers {
+                application_ids: self.state.draft_callers().await?,
+            })
+            .with_authentication()
+            .send_to(message_id.chain_id);
         Ok(())
     }
 }
@@ -40,6 +40,23 @@ pub struct Content {
     pub dislikes: u64,
     pub accounts: HashMap<Owner, bool>,
     pub created_at: Timestamp,
+    /// Time the content becomes visible, None if it's published at once
+    pub publish_at: Option<Timestamp>,
+    /// Average review rubric score in hundredths, could be used to rank contents
+    pub review_score: Option<u16>,
+}
+
+/// Draft is only stored on the author's chain and never broadcast
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Draft {
+    pub cid: String,
+    pub author: Owner,
+    pub title: String,
+    pub content: String,
+    pub cover: String,
+    pub abbreviation: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -67,6 +84,8 @@ pub enum Operation {
         cover: String,
         abbreviation: String,
         author: Owner,
+        publish_at: Option<Timestamp>,
+        review_score: Option<u16>,
     },
     Recommend {
         cid: String,
@@ -82,6 +101,26 @@ pub enum Operation {
     ContentAuthor {
         cid: String,
     },
+    /// Release a batch of due scheduled contents, call again while any is left
+    ReleaseScheduled,
+    SaveDraft {
+        cid: String,
+        title: String,
+        content: String,
+        cover: String,
+        abbreviation: String,
+    },
+    DeleteDraft {
+        cid: String,
+    },
+    /// Called by review application to take the draft out for submission
+    SubmitDraft {
+        cid: String,
+    },
+    /// Applications allowed to take drafts out for submission, review application only
+    SetDraftCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -103,6 +142,8 @@ pub enum Message {
         cover: String,
         abbreviation: String,
         author: Owner,
+        publish_at: Option<Timestamp>,
+        review_score: Option<u16>,
     },
     Recommend {
         cid: String,
@@ -116,6 +157,14 @@ pub enum Message {
         commentor: Owner,
     },
     RequestSubscribe,
+    ReleaseScheduled,
+    SetDraftCallers {
+        application_ids: Vec<ApplicationId>,
+    },
+    /// Scheduled contents released by creation chain, subscribers don't use their own clock
+    ScheduledReleased {
+        cids: Vec<String>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -123,6 +172,7 @@ pub enum FeedResponse {
     #[default]
     Ok,
     ContentAuthor(Option<Owner>),
+    Draft(Draft),
 }
 
 /// An error that can occur during the contract execution.
@@ -163,6 +213,18 @@ pub enum FeedError {
     #[error("Invalid message id")]
     InvalidMessageId,
 
+    #[error("Draft not exist")]
+    DraftNotExist,
+
+    #[error("Invalid caller")]
+    InvalidCaller,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
+
+    #[error("Invalid publish time")]
+    InvalidPublishTime,
+
     #[error("View error")]
     ViewError(#[from] linera_views::views::ViewError),
 }
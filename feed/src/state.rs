@@ -1,8 +1,10 @@
+use std::collections::{BTreeMap, HashMap};
+
 use async_graphql::SimpleObject;
-use feed::{Content, FeedError, InstantiationArgument};
+use feed::{Content, Draft, FeedError, InstantiationArgument};
 use linera_sdk::{
-    base::{Owner, Timestamp},
-    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+    base::{ApplicationId, Owner, Timestamp},
+    views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
 };
 
 #[derive(RootView, SimpleObject)]
@@ -16,6 +18,15 @@ pub struct Feed {
     pub react_accounts: MapView<Owner, Timestamp>,
     pub collection_recommends: MapView<u64, Vec<String>>,
     pub collection_comments: MapView<u64, Vec<String>>,
+    /// Approved contents waiting for their publish time
+    #[graphql(skip)]
+    pub scheduled_contents: MapView<String, Content>,
+    /// Drafts keyed by author then cid
+    pub drafts: MapView<Owner, HashMap<String, Draft>>,
+    /// Scheduled content cids indexed by publish time
+    #[graphql(skip)]
+    pub schedule_index: RegisterView<BTreeMap<Timestamp, Vec<String>>>,
+    pub draft_callers: SetView<ApplicationId>,
 }
 
 #[allow(dead_code)]
@@ -51,6 +62,60 @@ impl Feed {
         }
     }
 
+    pub(crate) async fn schedule_content(&mut self, content: Content) -> Result<(), FeedError> {
+        if self.contents.contains_key(&content.cid).await?
+            || self.scheduled_contents.contains_key(&content.cid).await?
+        {
+            return Err(FeedError::AlreadyExists);
+        }
+        let Some(publish_at) = content.publish_at else {
+            return Err(FeedError::InvalidPublishTime);
+        };
+        self.schedule_index
+            .get_mut()
+            .entry(publish_at)
+            .or_default()
+            .push(content.cid.clone());
+        self.scheduled_contents
+            .insert(&content.clone().cid, content)?;
+        Ok(())
+    }
+
+    /// At most limit contents published at or before now, earliest first
+    pub(crate) fn due_scheduled_contents(&self, now: Timestamp, limit: usize) -> Vec<String> {
+        self.schedule_index
+            .get()
+            .range(..=now)
+            .flat_map(|(_, cids)| cids.iter().cloned())
+            .take(limit)
+            .collect()
+    }
+
+    pub(crate) async fn release_scheduled_contents(
+        &mut self,
+        cids: Vec<String>,
+    ) -> Result<Vec<Content>, FeedError> {
+        let mut released = Vec::new();
+        for cid in cids {
+            let Some(content) = self.scheduled_contents.get(&cid).await? else {
+                continue;
+            };
+            self.scheduled_contents.remove(&cid)?;
+            if let Some(publish_at) = content.publish_at {
+                let index = self.schedule_index.get_mut();
+                if let Some(scheduled) = index.get_mut(&publish_at) {
+                    scheduled.retain(|_cid| *_cid != cid);
+                    if scheduled.is_empty() {
+                        index.remove(&publish_at);
+                    }
+                }
+            }
+            self.create_content(content.clone(), content.author).await?;
+            released.push(content);
+        }
+        Ok(released)
+    }
+
     pub(crate) async fn like_content(
         &mut self,
         ccid: String,
@@ -134,6 +199,51 @@ impl Feed {
         Ok(())
     }
 
+    /// Saving an existing draft again keeps its created_at
+    pub(crate) async fn save_draft(&mut self, mut draft: Draft) -> Result<(), FeedError> {
+        let author = draft.author;
+        let mut drafts = self.drafts.get(&author).await?.unwrap_or_default();
+        if let Some(saved) = drafts.get(&draft.cid) {
+            draft.created_at = saved.created_at;
+        }
+        drafts.insert(draft.cid.clone(), draft);
+        self.drafts.insert(&author, drafts)?;
+        Ok(())
+    }
+
+    pub(crate) async fn delete_draft(
+        &mut self,
+        author: Owner,
+        cid: String,
+    ) -> Result<Draft, FeedError> {
+        let mut drafts = self.drafts.get(&author).await?.unwrap_or_default();
+        let draft = drafts.remove(&cid).ok_or(FeedError::DraftNotExist)?;
+        if drafts.is_empty() {
+            self.drafts.remove(&author)?;
+        } else {
+            self.drafts.insert(&author, drafts)?;
+        }
+        Ok(draft)
+    }
+
+    pub(crate) async fn set_draft_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        self.draft_callers.clear();
+        application_ids
+            .iter()
+            .for_each(|application_id| self.draft_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn draft_callers(&self) -> Result<Vec<ApplicationId>, FeedError> {
+        Ok(self.draft_callers.indices().await?)
+    }
+
+    pub(crate) async fn is_draft_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, FeedError> {
+        Ok(self.draft_callers.contains(&application_id).await?)
+    }
+
     pub(crate) async fn content_author(&self, cid: String) -> Result<Owner, FeedError> {
         match self.contents.get(&cid).await {
             Ok(Some(content)) => Ok(content.author),
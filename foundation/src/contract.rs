@@ -6,10 +6,11 @@ use std::collections::HashSet;
 
 use self::state::Foundation;
 use foundation::{
-    FoundationError, FoundationResponse, InstantiationArgument, Message, Operation, RewardType,
+    escrow_owner, FoundationError, FoundationResponse, InstantiationArgument, Message, Operation,
+    RewardType,
 };
 use linera_sdk::{
-    base::{Amount, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
@@ -75,6 +76,7 @@ impl Contract for FoundationContract {
             }
             Operation::Deposit { from, amount } => self
                 .on_op_deposit(from, amount)
+                .await
                 .expect("Failed OP: deposit"),
             Operation::Lock {
                 activity_id,
@@ -91,7 +93,11 @@ impl Contract for FoundationContract {
                 .expect("Failed OP: reward"),
             Operation::Transfer { from, to, amount } => self
                 .on_op_transfer(from, to, amount)
+                .await
                 .expect("Failed OP: transfer"),
+            Operation::SetTransferCallers { application_ids } => self
+                .on_op_set_transfer_callers(application_ids)
+                .expect("Failed OP: set transfer callers"),
         }
     }
 
@@ -107,6 +113,7 @@ impl Contract for FoundationContract {
                 .expect("Failed MSG: user deposit"),
             Message::RequestSubscribe => self
                 .on_msg_request_subscribe()
+                .await
                 .expect("Failed MSG: subscribe"),
             Message::Deposit { from, amount } => self
                 .on_msg_deposit(from, amount)
@@ -147,6 +154,10 @@ impl Contract for FoundationContract {
                 )
                 .await
                 .expect("Failed MSG: activity rewards"),
+            Message::SetTransferCallers { application_ids } => self
+                .on_msg_set_transfer_callers(application_ids)
+                .await
+                .expect("Failed MSG: set transfer callers"),
         }
     }
 
@@ -192,6 +203,20 @@ impl FoundationContract {
         }
     }
 
+    /// Balances are only moved by allowed applications, from the signer or the caller's escrow
+    async fn validate_transfer_caller(&mut self, from: Owner) -> Result<(), FoundationError> {
+        let Some(caller_id) = self.runtime.authenticated_caller_id() else {
+            return Err(FoundationError::InvalidCaller);
+        };
+        if !self.state.is_transfer_caller(caller_id).await? {
+            return Err(FoundationError::InvalidCaller);
+        }
+        if self.runtime.authenticated_signer() != Some(from) && from != escrow_owner(caller_id) {
+            return Err(FoundationError::InvalidCaller);
+        }
+        Ok(())
+    }
+
     fn on_op_request_subscribe(&mut self) -> Result<FoundationResponse, FoundationError> {
         self.runtime
             .prepare_message(Message::RequestSubscribe)
@@ -239,16 +264,21 @@ impl FoundationContract {
         ))
     }
 
-    fn on_op_deposit(
+    async fn on_op_deposit(
         &mut self,
         from: Owner,
         amount: Amount,
     ) -> Result<FoundationResponse, FoundationError> {
+        self.validate_transfer_caller(from).await?;
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            self.on_msg_deposit(from, amount).await?;
+            return Ok(FoundationResponse::Ok);
+        }
         self.runtime
             .prepare_message(Message::Deposit { from, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        Ok(FoundationResponse::Ok)
+        Ok(FoundationResponse::Pending)
     }
 
     fn on_op_lock(
@@ -283,16 +313,35 @@ impl FoundationContract {
         Ok(FoundationResponse::Ok)
     }
 
-    fn on_op_transfer(
+    async fn on_op_transfer(
         &mut self,
         from: Owner,
         to: Owner,
         amount: Amount,
     ) -> Result<FoundationResponse, FoundationError> {
+        self.validate_transfer_caller(from).await?;
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            self.on_msg_transfer(from, to, amount).await?;
+            return Ok(FoundationResponse::Ok);
+        }
         self.runtime
             .prepare_message(Message::Transfer { from, to, amount })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(FoundationResponse::Pending)
+    }
+
+    fn on_op_set_transfer_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<FoundationResponse, FoundationError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Err(FoundationError::OperationNotAllowed);
+        }
+        self.runtime
+            .prepare_message(Message::SetTransferCallers { application_ids })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
         Ok(FoundationResponse::Ok)
     }
 
@@ -317,7 +366,7 @@ impl FoundationContract {
         Ok(())
     }
 
-    fn on_msg_request_subscribe(&mut self) -> Result<(), FoundationError> {
+    async fn on_msg_request_subscribe(&mut self) -> Result<(), FoundationError> {
         let message_id = self.require_message_id()?;
         // The subscribe message must be from another chain
         if message_id.chain_id == self.runtime.application_id().creation.chain_id {
@@ -327,6 +376,33 @@ impl FoundationContract {
             message_id.chain_id,
             ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()),
         );
+        self.runtime
+            .prepare_message(Message::SetTransferCallers {
+                application_ids: self.state.transfer_callers().await?,
+            })
+            .with_authentication()
+            .send_to(message_id.chain_id);
+        Ok(())
+    }
+
+    async fn on_msg_set_transfer_callers(
+        &mut self,
+        application_ids: Vec<ApplicationId>,
+    ) -> Result<(), FoundationError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(FoundationError::OperationNotAllowed);
+        }
+        self.state
+            .set_transfer_callers(application_ids.clone())
+            .await;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetTransferCallers { application_ids })
+            .with_authentication()
+            .send_to(dest);
         Ok(())
     }
 
@@ -2,11 +2,15 @@ use std::collections::HashSet;
 
 use async_graphql::{Enum, Request, Response};
 use linera_sdk::{
-    base::{Amount, ArithmeticError, ContractAbi, Owner, ServiceAbi},
+    base::{
+        Amount, ApplicationId, ArithmeticError, BcsHashable, ContractAbi, CryptoHash, Owner,
+        ServiceAbi,
+    },
     graphql::GraphQLMutationRoot,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+
 pub struct FoundationAbi;
 
 impl ContractAbi for FoundationAbi {
@@ -33,6 +37,23 @@ pub enum FoundationResponse {
     #[default]
     Ok,
     Balance(Amount),
+    /// Deposit or transfer called off creation chain is sent there and not applied yet.
+    /// Applications which pay with tokens in the same transaction, like market, must be created
+    /// on the creation chain of foundation, otherwise every payment fails
+    Pending,
+}
+
+/// Application escrow is kept in foundation balance of this owner, nobody holds its key
+#[derive(Deserialize, Serialize)]
+struct Escrow {
+    application_id: ApplicationId,
+}
+
+impl BcsHashable for Escrow {}
+
+/// Owner of the escrow balance of the application, only the application could transfer from it
+pub fn escrow_owner(application_id: ApplicationId) -> Owner {
+    Owner(CryptoHash::new(&Escrow { application_id }))
 }
 
 #[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
@@ -79,6 +100,10 @@ pub enum Operation {
     Balance {
         owner: Owner,
     },
+    /// Applications allowed to deposit and transfer user balances, market only
+    SetTransferCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,6 +144,9 @@ pub enum Message {
         activity_id: u64,
         amount: Amount,
     },
+    SetTransferCallers {
+        application_ids: Vec<ApplicationId>,
+    },
 }
 
 /// An error that can occur during the contract execution.
@@ -164,4 +192,10 @@ pub enum FoundationError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Invalid caller")]
+    InvalidCaller,
+
+    #[error("Operation not allowed")]
+    OperationNotAllowed,
 }
@@ -3,8 +3,8 @@ use std::collections::HashSet;
 use async_graphql::SimpleObject;
 use foundation::{FoundationError, InstantiationArgument, RewardType};
 use linera_sdk::{
-    base::{Amount, Owner},
-    views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
+    base::{Amount, ApplicationId, Owner},
+    views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
 };
 
 #[derive(RootView, SimpleObject)]
@@ -21,6 +21,7 @@ pub struct Foundation {
     pub activity_reward_balance: RegisterView<Amount>,
     pub activity_lock_funds: MapView<u64, Amount>,
     pub user_balances: MapView<Owner, Amount>,
+    pub transfer_callers: SetView<ApplicationId>,
 }
 
 #[allow(dead_code)]
@@ -255,6 +256,24 @@ impl Foundation {
             .unwrap_or_default())
     }
 
+    pub(crate) async fn set_transfer_callers(&mut self, application_ids: Vec<ApplicationId>) {
+        self.transfer_callers.clear();
+        application_ids
+            .iter()
+            .for_each(|application_id| self.transfer_callers.insert(application_id).unwrap())
+    }
+
+    pub(crate) async fn transfer_callers(&self) -> Result<Vec<ApplicationId>, FoundationError> {
+        Ok(self.transfer_callers.indices().await?)
+    }
+
+    pub(crate) async fn is_transfer_caller(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<bool, FoundationError> {
+        Ok(self.transfer_callers.contains(&application_id).await?)
+    }
+
     pub(crate) async fn spend_activity_funds(
         &mut self,
         activity_id: u64,
@@ -2,17 +2,68 @@
 
 mod state;
 
+use std::collections::HashMap;
+
 use self::state::Market;
-use credit::CreditAbi;
-use foundation::FoundationAbi;
+use credit::{CreditAbi, CreditResponse};
+use foundation::{FoundationAbi, FoundationResponse};
 use linera_sdk::{
-    base::{Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, WithContractAbi},
+    base::{
+        Amount, ApplicationId, ChannelName, Destination, MessageId, Owner, Timestamp,
+        WithContractAbi,
+    },
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use market::{InstantiationArgument, MarketError, MarketParameters, Message, Operation};
+use market::{
+    Auction, AuctionKind, AuctionStatus, Collection, InstantiationArgument, MarketError,
+    MarketParameters, Message, MintPolicy, NFTMetadata, Operation, NFT,
+};
 
 const SUBSCRIPTION_CHANNEL: &[u8] = b"subscriptions";
+/// Offers expired and refunded by one ExpireOffers message
+const MAX_EXPIRE_BATCH: usize = 20;
+
+/// Collection requested by its publisher, usually through review
+struct NewCollection {
+    base_uri: String,
+    price: Option<Amount>,
+    name: String,
+    uris: Vec<String>,
+    publisher: Owner,
+    review_score: Option<u16>,
+    royalty_percent: Option<u8>,
+}
+
+impl NewCollection {
+    fn collection(&self, now: Timestamp) -> Collection {
+        Collection {
+            collection_id: 0,
+            base_uri: self.base_uri.clone(),
+            uris: self.uris.clone(),
+            nfts: HashMap::new(),
+            price: self.price,
+            name: self.name.clone(),
+            created_at: now,
+            publisher: self.publisher,
+            review_score: self.review_score,
+            royalty_percent: self.royalty_percent.unwrap_or_default(),
+            mint_policy: MintPolicy::default(),
+        }
+    }
+
+    fn message(self) -> Message {
+        Message::CreateCollection {
+            base_uri: self.base_uri,
+            price: self.price,
+            name: self.name,
+            uris: self.uris,
+            publisher: self.publisher,
+            review_score: self.review_score,
+            royalty_percent: self.royalty_percent,
+        }
+    }
+}
 
 pub struct MarketContract {
     state: Market,
@@ -40,6 +91,8 @@ impl Contract for MarketContract {
     async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
         self.runtime.application_parameters();
         self.state.instantiate_market(argument).await;
+        // New application has no legacy ownership to migrate
+        self.state.ownership_migrated.set(true);
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
@@ -52,8 +105,9 @@ impl Contract for MarketContract {
                 uri_index,
                 price,
                 name,
+                metadata,
             } => self
-                .on_op_mint_nft(collection_id, uri_index, price, name)
+                .on_op_mint_nft(collection_id, uri_index, price, name, metadata)
                 .expect("Failed OP: mint nft"),
             Operation::BuyNFT {
                 collection_id,
@@ -99,13 +153,76 @@ impl Contract for MarketContract {
                 name,
                 uris,
                 publisher,
+                review_score,
+                royalty_percent,
             } => self
-                .on_op_create_collection(base_uri, price, name, uris, publisher)
+                .on_op_create_collection(NewCollection {
+                    base_uri,
+                    price,
+                    name,
+                    uris,
+                    publisher,
+                    review_score,
+                    royalty_percent,
+                })
                 .expect("Failed OP: create collection"),
+            Operation::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                start_price,
+                reserve,
+                end_at,
+            } => self
+                .on_op_create_auction(collection_id, token_id, kind, start_price, reserve, end_at)
+                .expect("Failed OP: create auction"),
+            Operation::Bid { auction_id, amount } => {
+                self.on_op_bid(auction_id, amount).expect("Failed OP: bid")
+            }
+            Operation::CancelAuction { auction_id } => self
+                .on_op_cancel_auction(auction_id)
+                .expect("Failed OP: cancel auction"),
+            Operation::SettleAuction { auction_id } => self
+                .on_op_settle_auction(auction_id)
+                .expect("Failed OP: settle auction"),
+            Operation::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                expires_at,
+            } => self
+                .on_op_make_offer(collection_id, token_id, amount, expires_at)
+                .expect("Failed OP: make offer"),
+            Operation::AcceptOffer { offer_id } => self
+                .on_op_accept_offer(offer_id)
+                .expect("Failed OP: accept offer"),
+            Operation::CancelOffer { offer_id } => self
+                .on_op_cancel_offer(offer_id)
+                .expect("Failed OP: cancel offer"),
+            Operation::ExpireOffers => self
+                .on_op_expire_offers()
+                .expect("Failed OP: expire offers"),
+            Operation::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            } => self
+                .on_op_transfer_nft(collection_id, token_id, to)
+                .expect("Failed OP: transfer nft"),
+            Operation::SetMintPolicy {
+                collection_id,
+                policy,
+            } => self
+                .on_op_set_mint_policy(collection_id, policy)
+                .expect("Failed OP: set mint policy"),
         }
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
+        self.state
+            .migrate_ownership()
+            .await
+            .expect("Failed MSG: migrate ownership");
         match message {
             Message::InstantiationArgument { argument } => self
                 .on_msg_instantiation_argument(argument)
@@ -117,8 +234,18 @@ impl Contract for MarketContract {
                 name,
                 uris,
                 publisher,
+                review_score,
+                royalty_percent,
             } => self
-                .on_msg_create_collection(base_uri, price, name, uris, publisher)
+                .on_msg_create_collection(NewCollection {
+                    base_uri,
+                    price,
+                    name,
+                    uris,
+                    publisher,
+                    review_score,
+                    royalty_percent,
+                })
                 .await
                 .expect("Failed MSG: create collection"),
             Message::MintNFT {
@@ -126,8 +253,9 @@ impl Contract for MarketContract {
                 uri_index,
                 price,
                 name,
+                metadata,
             } => self
-                .on_msg_mint_nft(collection_id, uri_index, price, name)
+                .on_msg_mint_nft(collection_id, uri_index, price, name, metadata)
                 .await
                 .expect("Failed MSG: mint nft"),
             Message::BuyNFT {
@@ -174,6 +302,69 @@ impl Contract for MarketContract {
             Message::RequestSubscribe => self
                 .on_msg_request_subscribe()
                 .expect("Failed MSG: subscribe"),
+            Message::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                start_price,
+                reserve,
+                end_at,
+            } => self
+                .on_msg_create_auction(collection_id, token_id, kind, start_price, reserve, end_at)
+                .await
+                .expect("Failed MSG: create auction"),
+            Message::Bid { auction_id, amount } => self
+                .on_msg_bid(auction_id, amount)
+                .await
+                .expect("Failed MSG: bid"),
+            Message::CancelAuction { auction_id } => self
+                .on_msg_cancel_auction(auction_id)
+                .await
+                .expect("Failed MSG: cancel auction"),
+            Message::SettleAuction { auction_id } => self
+                .on_msg_settle_auction(auction_id)
+                .await
+                .expect("Failed MSG: settle auction"),
+            Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                expires_at,
+            } => self
+                .on_msg_make_offer(collection_id, token_id, amount, expires_at)
+                .await
+                .expect("Failed MSG: make offer"),
+            Message::AcceptOffer { offer_id } => self
+                .on_msg_accept_offer(offer_id)
+                .await
+                .expect("Failed MSG: accept offer"),
+            Message::CancelOffer { offer_id } => self
+                .on_msg_cancel_offer(offer_id)
+                .await
+                .expect("Failed MSG: cancel offer"),
+            Message::ExpireOffers => self
+                .on_msg_expire_offers()
+                .await
+                .expect("Failed MSG: expire offers"),
+            Message::OffersExpired { offer_ids } => self
+                .on_msg_offers_expired(offer_ids)
+                .await
+                .expect("Failed MSG: offers expired"),
+            Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            } => self
+                .on_msg_transfer_nft(collection_id, token_id, to)
+                .await
+                .expect("Failed MSG: transfer nft"),
+            Message::SetMintPolicy {
+                collection_id,
+                policy,
+            } => self
+                .on_msg_set_mint_policy(collection_id, policy)
+                .await
+                .expect("Failed MSG: set mint policy"),
         }
     }
 
@@ -191,6 +382,7 @@ impl MarketContract {
         self.runtime.application_parameters().foundation_app_id
     }
 
+    /// Only a transfer settled in this transaction pays, see CreditResponse::Pending
     async fn transfer_credits(
         &mut self,
         from: Owner,
@@ -199,17 +391,44 @@ impl MarketContract {
     ) -> Result<(), MarketError> {
         let call = credit::Operation::Transfer { from, to, amount };
         let credit_app_id = self.credit_app_id();
-        let _ = self.runtime.call_application(true, credit_app_id, &call);
-        Ok(())
+        match self.runtime.call_application(true, credit_app_id, &call) {
+            CreditResponse::Ok => Ok(()),
+            _ => Err(MarketError::PaymentFailed),
+        }
+    }
+
+    async fn credits_balance(&mut self, owner: Owner) -> Result<Amount, MarketError> {
+        let call = credit::Operation::Balance { owner };
+        let credit_app_id = self.credit_app_id();
+        match self.runtime.call_application(true, credit_app_id, &call) {
+            CreditResponse::Balance(amount) => Ok(amount),
+            _ => Err(MarketError::PaymentFailed),
+        }
+    }
+
+    async fn tokens_balance(&mut self, owner: Owner) -> Result<Amount, MarketError> {
+        let call = foundation::Operation::Balance { owner };
+        let foundation_app_id = self.foundation_app_id();
+        match self
+            .runtime
+            .call_application(true, foundation_app_id, &call)
+        {
+            FoundationResponse::Balance(amount) => Ok(amount),
+            _ => Err(MarketError::PaymentFailed),
+        }
     }
 
+    /// Only a deposit settled in this transaction pays, see FoundationResponse::Pending
     async fn deposit_commission(&mut self, from: Owner, amount: Amount) -> Result<(), MarketError> {
         let call = foundation::Operation::Deposit { from, amount };
         let foundation_app_id = self.foundation_app_id();
-        let _ = self
+        match self
             .runtime
-            .call_application(true, foundation_app_id, &call);
-        Ok(())
+            .call_application(true, foundation_app_id, &call)
+        {
+            FoundationResponse::Ok => Ok(()),
+            _ => Err(MarketError::PaymentFailed),
+        }
     }
 
     async fn transfer_tokens(
@@ -220,9 +439,57 @@ impl MarketContract {
     ) -> Result<(), MarketError> {
         let call = foundation::Operation::Transfer { from, to, amount };
         let foundation_app_id = self.foundation_app_id();
-        let _ = self
+        match self
             .runtime
-            .call_application(true, foundation_app_id, &call);
+            .call_application(true, foundation_app_id, &call)
+        {
+            FoundationResponse::Ok => Ok(()),
+            _ => Err(MarketError::PaymentFailed),
+        }
+    }
+
+    /// Auction bids and offers are escrowed in foundation balance of this owner
+    fn escrow_owner(&mut self) -> Owner {
+        foundation::escrow_owner(self.runtime.application_id().forget_abi())
+    }
+
+    /// Pay sale price to seller after commission and publisher royalty
+    async fn pay_sale(
+        &mut self,
+        from: Owner,
+        seller: Owner,
+        collection_id: u64,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        let fee = self.state.trading_fee(price).await?;
+        let (publisher, royalty) = self.state.royalty(collection_id, seller, price).await?;
+        self.transfer_tokens(from, seller, price.try_sub(fee)?.try_sub(royalty)?)
+            .await?;
+        if royalty > Amount::ZERO {
+            self.transfer_tokens(from, publisher, royalty).await?;
+        }
+        self.deposit_commission(from, fee).await?;
+        Ok(())
+    }
+
+    /// Expire offers on creation chain, refund escrow and broadcast expired offers to subscribers
+    async fn expire_offers(&mut self) -> Result<(), MarketError> {
+        let offer_ids = self
+            .state
+            .expired_offers(self.runtime.system_time(), MAX_EXPIRE_BATCH);
+        if offer_ids.is_empty() {
+            return Ok(());
+        }
+        let escrow = self.escrow_owner();
+        for offer in self.state.expire_offers(offer_ids.clone()).await? {
+            self.transfer_tokens(escrow, offer.buyer, offer.amount)
+                .await?;
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::OffersExpired { offer_ids })
+            .with_authentication()
+            .send_to(dest);
         Ok(())
     }
 
@@ -246,6 +513,7 @@ impl MarketContract {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        metadata: Option<NFTMetadata>,
     ) -> Result<(), MarketError> {
         self.runtime
             .prepare_message(Message::MintNFT {
@@ -253,6 +521,7 @@ impl MarketContract {
                 uri_index,
                 price,
                 name,
+                metadata,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -346,25 +615,134 @@ impl MarketContract {
         Ok(())
     }
 
-    fn on_op_create_collection(
+    fn on_op_create_collection(&mut self, collection: NewCollection) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(collection.message())
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        // TODO: send instantiation argument to subscriber
+        Ok(())
+    }
+
+    fn on_op_create_auction(
         &mut self,
-        base_uri: String,
-        price: Option<Amount>,
-        name: String,
-        uris: Vec<String>,
-        publisher: Owner,
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        start_price: Amount,
+        reserve: Amount,
+        end_at: Timestamp,
     ) -> Result<(), MarketError> {
         self.runtime
-            .prepare_message(Message::CreateCollection {
-                base_uri,
-                price,
-                name,
-                uris,
-                publisher,
+            .prepare_message(Message::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                start_price,
+                reserve,
+                end_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_bid(&mut self, auction_id: u64, amount: Amount) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::Bid { auction_id, amount })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_cancel_auction(&mut self, auction_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::CancelAuction { auction_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_settle_auction(&mut self, auction_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::SettleAuction { auction_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_make_offer(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        expires_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                expires_at,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_accept_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::AcceptOffer { offer_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_cancel_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::CancelOffer { offer_id })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_expire_offers(&mut self) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::ExpireOffers)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_transfer_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(())
+    }
+
+    fn on_op_set_mint_policy(
+        &mut self,
+        collection_id: u64,
+        policy: MintPolicy,
+    ) -> Result<(), MarketError> {
+        self.runtime
+            .prepare_message(Message::SetMintPolicy {
+                collection_id,
+                policy,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
-        // TODO: send instantiation argument to subscriber
         Ok(())
     }
 
@@ -378,34 +756,17 @@ impl MarketContract {
 
     async fn on_msg_create_collection(
         &mut self,
-        base_uri: String,
-        price: Option<Amount>,
-        name: String,
-        uris: Vec<String>,
-        publisher: Owner,
+        collection: NewCollection,
     ) -> Result<(), MarketError> {
         self.state
-            .create_collection(
-                publisher,
-                base_uri.clone(),
-                price,
-                name.clone(),
-                uris.clone(),
-                self.runtime.system_time(),
-            )
+            .create_collection(collection.collection(self.runtime.system_time()))
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::CreateCollection {
-                base_uri,
-                price,
-                name,
-                uris,
-                publisher,
-            })
+            .prepare_message(collection.message())
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -417,18 +778,35 @@ impl MarketContract {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        metadata: Option<NFTMetadata>,
     ) -> Result<(), MarketError> {
         let owner = self.require_authenticated_signer()?;
-        self.state
-            .mint_nft(
-                owner,
-                collection_id,
-                uri_index,
-                price,
-                name.clone(),
-                self.runtime.system_time(),
-            )
-            .await?;
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            self.state
+                .validate_mint_time(collection_id, owner, self.runtime.system_time())
+                .await?;
+            let collection = self.state.collection(collection_id).await?;
+            self.state.validate_minter(&collection, owner).await?;
+            if let Some((publisher, price)) = self.state.mint_payment(collection_id, owner).await? {
+                if self.tokens_balance(owner).await? < price {
+                    return Err(MarketError::InsufficientBalance);
+                }
+                let fee = self.state.trading_fee(price).await?;
+                self.transfer_tokens(owner, publisher, price.saturating_sub(fee))
+                    .await?;
+                self.deposit_commission(owner, fee).await?;
+            }
+        }
+        let nft = NFT {
+            token_id: 0,
+            uri_index,
+            price,
+            on_sale: true,
+            minted_at: self.runtime.system_time(),
+            name: name.clone(),
+            metadata: metadata.clone().unwrap_or_default(),
+        };
+        self.state.mint_nft(owner, collection_id, nft).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -439,6 +817,7 @@ impl MarketContract {
                 uri_index,
                 price,
                 name,
+                metadata,
             })
             .with_authentication()
             .send_to(dest);
@@ -454,19 +833,32 @@ impl MarketContract {
         let buyer = self.require_authenticated_signer()?;
         if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
             let owner = self.state.nft_owner(collection_id, token_id).await?;
-            let price = self.state.nft_price(collection_id, token_id).await?;
-            let fee = self.state.trading_fee(price).await?;
-            let discount = self.state.credits_to_tokens(credits).await?;
-            self.transfer_credits(buyer, owner, credits).await?;
-            self.transfer_tokens(
-                buyer,
-                owner,
-                price.saturating_sub(fee).saturating_sub(discount),
-            )
-            .await?;
-            self.deposit_commission(buyer, fee).await?;
+            let plan = self
+                .state
+                .payment_plan(collection_id, token_id, credits)
+                .await?;
+            // Both legs settle in this transaction and any failed transfer rolls back the whole
+            // purchase, balances are checked first only to fail with a clear error
+            if self.credits_balance(buyer).await? < plan.credits {
+                return Err(MarketError::InsufficientCredits);
+            }
+            if self.tokens_balance(buyer).await? < plan.tokens {
+                return Err(MarketError::InsufficientBalance);
+            }
+            if plan.credits > Amount::ZERO {
+                self.transfer_credits(buyer, owner, plan.credits).await?;
+            }
+            self.transfer_tokens(buyer, owner, plan.seller_tokens)
+                .await?;
+            if plan.royalty > Amount::ZERO {
+                self.transfer_tokens(buyer, plan.publisher, plan.royalty)
+                    .await?;
+            }
+            self.deposit_commission(buyer, plan.fee).await?;
         }
-        self.state.buy_nft(buyer, collection_id, token_id).await?;
+        self.state
+            .buy_nft(buyer, collection_id, token_id, self.runtime.system_time())
+            .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -507,7 +899,13 @@ impl MarketContract {
     ) -> Result<(), MarketError> {
         let setter = self.require_authenticated_signer()?;
         self.state
-            .update_nft_price(setter, collection_id, token_id, price)
+            .update_nft_price(
+                setter,
+                collection_id,
+                token_id,
+                price,
+                self.runtime.system_time(),
+            )
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
@@ -593,6 +991,298 @@ impl MarketContract {
         Ok(())
     }
 
+    async fn on_msg_create_auction(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        start_price: Amount,
+        reserve: Amount,
+        end_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let seller = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id
+            && end_at <= now
+        {
+            return Err(MarketError::InvalidAuction);
+        }
+        let auction = Auction {
+            auction_id: 0,
+            collection_id,
+            token_id,
+            seller,
+            kind,
+            start_price,
+            reserve,
+            created_at: now,
+            end_at,
+            highest_bid: None,
+            status: AuctionStatus::Open,
+        };
+        self.state.create_auction(auction).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CreateAuction {
+                collection_id,
+                token_id,
+                kind,
+                start_price,
+                reserve,
+                end_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_bid(&mut self, auction_id: u64, amount: Amount) -> Result<(), MarketError> {
+        let bidder = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        if creation_chain {
+            self.state
+                .validate_bid_time(auction_id, amount, now)
+                .await?;
+            if self.tokens_balance(bidder).await? < amount {
+                return Err(MarketError::InsufficientBalance);
+            }
+            // Bid is recorded only after it is paid, English bid into escrow and Dutch bid to
+            // seller as the sale
+            let auction = self.state.auction(auction_id).await?;
+            match auction.kind {
+                AuctionKind::English => {
+                    let escrow = self.escrow_owner();
+                    self.transfer_tokens(bidder, escrow, amount).await?;
+                }
+                AuctionKind::Dutch => {
+                    self.pay_sale(bidder, auction.seller, auction.collection_id, amount)
+                        .await?;
+                }
+            }
+        }
+        let outbid = self.state.bid(bidder, auction_id, amount, now).await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        if let Some(outbid) = outbid {
+            let escrow = self.escrow_owner();
+            self.transfer_tokens(escrow, outbid.bidder, outbid.amount)
+                .await?;
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Bid { auction_id, amount })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_cancel_auction(&mut self, auction_id: u64) -> Result<(), MarketError> {
+        let seller = self.require_authenticated_signer()?;
+        self.state.cancel_auction(seller, auction_id).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CancelAuction { auction_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    /// Anyone could settle an ended auction
+    async fn on_msg_settle_auction(&mut self, auction_id: u64) -> Result<(), MarketError> {
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        if creation_chain {
+            self.state
+                .validate_settle_time(auction_id, self.runtime.system_time())
+                .await?;
+        }
+        let auction = self
+            .state
+            .settle_auction(auction_id, self.runtime.system_time())
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        if let Some(bid) = auction.highest_bid {
+            let escrow = self.escrow_owner();
+            match auction.status {
+                AuctionStatus::Sold => {
+                    self.pay_sale(escrow, auction.seller, auction.collection_id, bid.amount)
+                        .await?
+                }
+                _ => self.transfer_tokens(escrow, bid.bidder, bid.amount).await?,
+            }
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SettleAuction { auction_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_make_offer(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        expires_at: Timestamp,
+    ) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        if creation_chain {
+            if expires_at <= now {
+                return Err(MarketError::InvalidOffer);
+            }
+            if self.tokens_balance(buyer).await? < amount {
+                return Err(MarketError::InsufficientBalance);
+            }
+            // Offer is recorded only after its amount is escrowed
+            let escrow = self.escrow_owner();
+            self.transfer_tokens(buyer, escrow, amount).await?;
+        }
+        self.state
+            .make_offer(buyer, collection_id, token_id, amount, expires_at, now)
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::MakeOffer {
+                collection_id,
+                token_id,
+                amount,
+                expires_at,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_accept_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() == self.runtime.application_id().creation.chain_id;
+        if creation_chain {
+            self.state
+                .validate_offer_time(offer_id, self.runtime.system_time())
+                .await?;
+        }
+        let offer = self
+            .state
+            .accept_offer(owner, offer_id, self.runtime.system_time())
+            .await?;
+        if !creation_chain {
+            return Ok(());
+        }
+        let escrow = self.escrow_owner();
+        self.pay_sale(escrow, owner, offer.collection_id, offer.amount)
+            .await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::AcceptOffer { offer_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_cancel_offer(&mut self, offer_id: u64) -> Result<(), MarketError> {
+        let buyer = self.require_authenticated_signer()?;
+        let offer = self.state.cancel_offer(buyer, offer_id).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let escrow = self.escrow_owner();
+        self.transfer_tokens(escrow, buyer, offer.amount).await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::CancelOffer { offer_id })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_expire_offers(&mut self) -> Result<(), MarketError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        self.expire_offers().await
+    }
+
+    async fn on_msg_offers_expired(&mut self, offer_ids: Vec<u64>) -> Result<(), MarketError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(MarketError::InvalidMessageId);
+        }
+        self.state.expire_offers(offer_ids).await?;
+        Ok(())
+    }
+
+    async fn on_msg_transfer_nft(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state
+            .transfer_nft(
+                owner,
+                collection_id,
+                token_id,
+                to,
+                self.runtime.system_time(),
+            )
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::TransferNFT {
+                collection_id,
+                token_id,
+                to,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_set_mint_policy(
+        &mut self,
+        collection_id: u64,
+        policy: MintPolicy,
+    ) -> Result<(), MarketError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state
+            .set_mint_policy(owner, collection_id, policy.clone())
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::SetMintPolicy {
+                collection_id,
+                policy,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
     fn on_msg_request_subscribe(&mut self) -> Result<(), MarketError> {
         let message_id = self.require_message_id()?;
         // The subscribe message must be from another chain
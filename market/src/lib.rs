@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, InputObject, OutputType, Request, Response, SimpleObject};
 use linera_sdk::{
     base::{Amount, ApplicationId, ArithmeticError, ContractAbi, Owner, ServiceAbi, Timestamp},
     graphql::GraphQLMutationRoot,
@@ -20,6 +20,9 @@ impl ServiceAbi for MarketAbi {
     type QueryResponse = Response;
 }
 
+/// Royalty of a collection could not exceed this percent of the sale price
+pub const MAX_ROYALTY_PERCENT: u8 = 50;
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct MarketParameters {
     pub credit_app_id: ApplicationId<credit::CreditAbi>,
@@ -37,6 +40,52 @@ pub struct NFT {
     pub on_sale: bool,
     pub minted_at: Timestamp,
     pub name: String,
+    pub metadata: NFTMetadata,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum TraitValueType {
+    String,
+    /// Integer or decimal
+    Number,
+    Boolean,
+    /// Timestamp in microseconds
+    Date,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "NFTTraitInput")]
+pub struct NFTTrait {
+    pub trait_type: String,
+    pub value_type: TraitValueType,
+    pub value: String,
+}
+
+/// Metadata of NFT set at mint time, trait types are unique in one NFT
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "NFTMetadataInput")]
+pub struct NFTMetadata {
+    pub description: Option<String>,
+    pub external_link: Option<String>,
+    pub traits: Vec<NFTTrait>,
+}
+
+#[derive(Debug, Clone, InputObject)]
+pub struct TraitFilter {
+    pub trait_type: String,
+    pub value: String,
+}
+
+/// Share of collection NFTs having the trait value
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct TraitRarity {
+    pub trait_type: String,
+    pub value: String,
+    pub count: u32,
+    /// Percent of collection supply in hundredths
+    pub rarity: u16,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -49,6 +98,178 @@ pub struct Collection {
     pub name: String,
     pub created_at: Timestamp,
     pub publisher: Owner,
+    /// Average review rubric score in hundredths, could be used to rank collections
+    pub review_score: Option<u16>,
+    /// Percent of resale price paid to publisher
+    pub royalty_percent: u8,
+    pub mint_policy: MintPolicy,
+}
+
+/// Publisher could always mint until max supply. Other minters pay collection price to publisher
+/// and are limited by mint window and wallet limit
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq,
+)]
+#[graphql(input_name = "MintPolicyInput")]
+pub struct MintPolicy {
+    pub max_supply: Option<u16>,
+    pub start_at: Option<Timestamp>,
+    pub end_at: Option<Timestamp>,
+    /// Max NFTs minted by each wallet other than publisher
+    pub wallet_limit: Option<u16>,
+    /// Anyone could mint, otherwise only owners in allowlist
+    pub public: bool,
+    pub allowlist: Vec<Owner>,
+}
+
+/// NFT resolved with its collection and owner
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Listing {
+    pub collection_id: u64,
+    pub collection_name: String,
+    pub base_uri: String,
+    pub uri: String,
+    pub token_id: u16,
+    pub name: String,
+    pub owner: Owner,
+    /// NFT price, or collection price if NFT has no own price
+    pub price: Option<Amount>,
+    pub on_sale: bool,
+    pub minted_at: Timestamp,
+    pub metadata: NFTMetadata,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum ListingOrder {
+    PriceAsc,
+    PriceDesc,
+    Newest,
+    Oldest,
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct ListingFilter {
+    pub on_sale: Option<bool>,
+    pub min_price: Option<Amount>,
+    pub max_price: Option<Amount>,
+    pub collection_id: Option<u64>,
+    /// Only NFTs having all these trait values
+    #[graphql(default)]
+    pub traits: Vec<TraitFilter>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct CollectionSummary {
+    pub collection: Collection,
+    /// Lowest price of NFTs on sale
+    pub floor_price: Option<Amount>,
+    pub owner_count: u32,
+    pub supply: u32,
+    pub volume: Amount,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(concrete(name = "ListingPage", params(Listing)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    /// Pass as after to fetch the next page, none on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Split of NFT price between buyer's credits and Linera tokens
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct PaymentPlan {
+    pub price: Amount,
+    /// Credits paid to seller, capped by max_credits_percent of price
+    pub credits: Amount,
+    /// Token value of credits, deducted from seller's tokens
+    pub discount: Amount,
+    /// Tokens paid by buyer in total
+    pub tokens: Amount,
+    pub seller_tokens: Amount,
+    pub fee: Amount,
+    pub publisher: Owner,
+    pub royalty: Amount,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum NFTEventKind {
+    Mint,
+    Sale,
+    Transfer,
+    PriceChange,
+}
+
+/// Provenance of NFT, from is None for mint, price is None for transfer
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct NFTEvent {
+    pub kind: NFTEventKind,
+    pub from: Option<Owner>,
+    pub to: Option<Owner>,
+    pub price: Option<Amount>,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum AuctionKind {
+    /// Price goes up with bids, highest bid wins when auction ends
+    English,
+    /// Price goes down from start price to reserve, first bid at current price wins
+    Dutch,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Open,
+    Sold,
+    /// Ended without bid reaching reserve
+    Unsold,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct AuctionBid {
+    pub bidder: Owner,
+    pub amount: Amount,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub collection_id: u64,
+    pub token_id: u16,
+    pub seller: Owner,
+    pub kind: AuctionKind,
+    pub start_price: Amount,
+    /// English auction is unsold below reserve, Dutch auction price stops decreasing at reserve
+    pub reserve: Amount,
+    pub created_at: Timestamp,
+    pub end_at: Timestamp,
+    /// Highest bid of English auction is escrowed until settled or outbid
+    pub highest_bid: Option<AuctionBid>,
+    pub status: AuctionStatus,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum OfferStatus {
+    Open,
+    Accepted,
+    Cancelled,
+    Expired,
+}
+
+/// Offer amount is escrowed until accepted, cancelled or expired
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Offer {
+    pub offer_id: u64,
+    pub collection_id: u64,
+    pub token_id: u16,
+    pub buyer: Owner,
+    pub amount: Amount,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub status: OfferStatus,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -67,6 +288,7 @@ pub enum Operation {
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        metadata: Option<NFTMetadata>,
     },
     BuyNFT {
         collection_id: u64,
@@ -100,6 +322,50 @@ pub enum Operation {
         name: String,
         uris: Vec<String>,
         publisher: Owner,
+        review_score: Option<u16>,
+        royalty_percent: Option<u8>,
+    },
+    CreateAuction {
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        start_price: Amount,
+        reserve: Amount,
+        end_at: Timestamp,
+    },
+    Bid {
+        auction_id: u64,
+        amount: Amount,
+    },
+    CancelAuction {
+        auction_id: u64,
+    },
+    SettleAuction {
+        auction_id: u64,
+    },
+    MakeOffer {
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        expires_at: Timestamp,
+    },
+    AcceptOffer {
+        offer_id: u64,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    /// Expire and refund a batch of expired offers, call again while any is left. Buyer could
+    /// also cancel an expired offer to get the refund
+    ExpireOffers,
+    TransferNFT {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    },
+    SetMintPolicy {
+        collection_id: u64,
+        policy: MintPolicy,
     },
 }
 
@@ -115,12 +381,15 @@ pub enum Message {
         name: String,
         uris: Vec<String>,
         publisher: Owner,
+        review_score: Option<u16>,
+        royalty_percent: Option<u8>,
     },
     MintNFT {
         collection_id: u64,
         uri_index: u16,
         price: Option<Amount>,
         name: String,
+        metadata: Option<NFTMetadata>,
     },
     BuyNFT {
         collection_id: u64,
@@ -148,6 +417,49 @@ pub enum Message {
         token_id: u16,
     },
     RequestSubscribe,
+    CreateAuction {
+        collection_id: u64,
+        token_id: u16,
+        kind: AuctionKind,
+        start_price: Amount,
+        reserve: Amount,
+        end_at: Timestamp,
+    },
+    Bid {
+        auction_id: u64,
+        amount: Amount,
+    },
+    CancelAuction {
+        auction_id: u64,
+    },
+    SettleAuction {
+        auction_id: u64,
+    },
+    MakeOffer {
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        expires_at: Timestamp,
+    },
+    AcceptOffer {
+        offer_id: u64,
+    },
+    CancelOffer {
+        offer_id: u64,
+    },
+    ExpireOffers,
+    TransferNFT {
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+    },
+    SetMintPolicy {
+        collection_id: u64,
+        policy: MintPolicy,
+    },
+    OffersExpired {
+        offer_ids: Vec<u64>,
+    },
 }
 
 /// An error that can occur during the contract execution.
@@ -211,4 +523,73 @@ pub enum MarketError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Invalid royalty")]
+    InvalidRoyalty,
+
+    #[error("Invalid auction")]
+    InvalidAuction,
+
+    #[error("Auction not open")]
+    AuctionNotOpen,
+
+    #[error("Auction not ended")]
+    AuctionNotEnded,
+
+    #[error("Auction ended")]
+    AuctionEnded,
+
+    #[error("Auction has bids")]
+    AuctionHasBids,
+
+    #[error("Bid too low")]
+    BidTooLow,
+
+    #[error("NFT in auction")]
+    NFTInAuction,
+
+    #[error("Insufficient balance")]
+    InsufficientBalance,
+
+    #[error("Payment failed")]
+    PaymentFailed,
+
+    #[error("Invalid offer")]
+    InvalidOffer,
+
+    #[error("Offer not open")]
+    OfferNotOpen,
+
+    #[error("Offer expired")]
+    OfferExpired,
+
+    #[error("Not offer owner")]
+    NotOfferOwner,
+
+    #[error("Invalid receiver")]
+    InvalidReceiver,
+
+    #[error("Credits exceed limit")]
+    CreditsExceedLimit,
+
+    #[error("Insufficient credits")]
+    InsufficientCredits,
+
+    #[error("Invalid mint policy")]
+    InvalidMintPolicy,
+
+    #[error("Mint not allowed")]
+    MintNotAllowed,
+
+    #[error("Mint window closed")]
+    MintWindowClosed,
+
+    #[error("Supply exceeded")]
+    SupplyExceeded,
+
+    #[error("Mint limit exceeded")]
+    MintLimitExceeded,
+
+    #[error("Invalid metadata")]
+    InvalidMetadata,
 }
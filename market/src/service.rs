@@ -3,12 +3,18 @@
 mod state;
 
 use self::state::Market;
-use async_graphql::{EmptySubscription, Request, Response, Schema};
+use async_graphql::{EmptySubscription, MergedObject, Object, Request, Response, Schema};
 use linera_sdk::{
-    base::WithServiceAbi, graphql::GraphQLMutationRoot, views::View, Service, ServiceRuntime,
+    base::{Amount, Owner, WithServiceAbi},
+    graphql::GraphQLMutationRoot,
+    views::View,
+    Service, ServiceRuntime,
 };
-use market::{MarketParameters, Operation};
-use std::sync::Arc;
+use market::{
+    Auction, AuctionStatus, CollectionSummary, Listing, ListingFilter, ListingOrder, MarketError,
+    MarketParameters, NFTEvent, Offer, OfferStatus, Operation, Page, PaymentPlan, TraitRarity,
+};
+use std::{collections::HashMap, sync::Arc};
 
 pub struct MarketService {
     state: Arc<Market>,
@@ -20,6 +26,95 @@ impl WithServiceAbi for MarketService {
     type Abi = market::MarketAbi;
 }
 
+struct MarketQuery {
+    state: Arc<Market>,
+}
+
+#[Object]
+impl MarketQuery {
+    async fn listings(
+        &self,
+        filter: Option<ListingFilter>,
+        order_by: Option<ListingOrder>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Listing>, MarketError> {
+        self.state
+            .listings(filter.unwrap_or_default(), order_by, after, first)
+            .await
+    }
+
+    async fn collection(&self, collection_id: u64) -> Result<CollectionSummary, MarketError> {
+        self.state.collection_summary(collection_id).await
+    }
+
+    async fn trait_rarities(&self, collection_id: u64) -> Result<Vec<TraitRarity>, MarketError> {
+        self.state.trait_rarities(collection_id).await
+    }
+
+    async fn owned_by(&self, owner: Owner) -> Result<Vec<Listing>, MarketError> {
+        self.state.owned_by(owner).await
+    }
+
+    async fn assets(&self, owner: Owner) -> Result<HashMap<u64, Vec<u16>>, MarketError> {
+        self.state.assets(owner).await
+    }
+
+    async fn nft_history(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<Vec<NFTEvent>, MarketError> {
+        self.state.nft_history(collection_id, token_id).await
+    }
+
+    async fn collection_volume(&self, collection_id: u64) -> Result<Amount, MarketError> {
+        self.state.collection_volume(collection_id).await
+    }
+
+    async fn payment_plan(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+        credits: Amount,
+    ) -> Result<PaymentPlan, MarketError> {
+        self.state
+            .payment_plan(collection_id, token_id, credits)
+            .await
+    }
+
+    async fn royalties(&self, publisher: Owner) -> Result<Amount, MarketError> {
+        self.state.royalties(publisher).await
+    }
+
+    async fn auctions(
+        &self,
+        status: Option<AuctionStatus>,
+        collection_id: Option<u64>,
+    ) -> Result<Vec<Auction>, MarketError> {
+        self.state.auctions(status, collection_id).await
+    }
+
+    async fn auction(&self, auction_id: u64) -> Result<Auction, MarketError> {
+        self.state.auction(auction_id).await
+    }
+
+    async fn offers(
+        &self,
+        status: Option<OfferStatus>,
+        collection_id: Option<u64>,
+        token_id: Option<u16>,
+        buyer: Option<Owner>,
+    ) -> Result<Vec<Offer>, MarketError> {
+        self.state
+            .offers(status, collection_id, token_id, buyer)
+            .await
+    }
+}
+
+#[derive(MergedObject)]
+struct QueryRoot(Arc<Market>, MarketQuery);
+
 impl Service for MarketService {
     type Parameters = MarketParameters;
 
@@ -34,7 +129,12 @@ impl Service for MarketService {
 
     async fn handle_query(&self, request: Request) -> Response {
         let schema = Schema::build(
-            self.state.clone(),
+            QueryRoot(
+                self.state.clone(),
+                MarketQuery {
+                    state: self.state.clone(),
+                },
+            ),
             Operation::mutation_root(),
             EmptySubscription,
         )
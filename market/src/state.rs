@@ -1,20 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use async_graphql::SimpleObject;
 use linera_sdk::{
     base::{Amount, Owner, Timestamp},
     views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
 };
-use market::{Collection, InstantiationArgument, MarketError, NFT};
+use market::{
+    Auction, AuctionBid, AuctionKind, AuctionStatus, Collection, CollectionSummary,
+    InstantiationArgument, Listing, ListingFilter, ListingOrder, MarketError, MintPolicy, NFTEvent,
+    NFTEventKind, NFTMetadata, Offer, OfferStatus, Page, PaymentPlan, TraitRarity, TraitValueType,
+    MAX_ROYALTY_PERCENT, NFT,
+};
+use serde::{de::DeserializeOwned, Serialize};
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
 pub struct Market {
     pub publisher_collections: MapView<Owner, Vec<u64>>,
-    /// owner, collection_id, token_id
+    /// Legacy owner index, sold tokens were kept in seller's entry. Replaced by owner_assets
+    #[graphql(skip)]
     pub _assets: MapView<Owner, HashMap<u64, Vec<u16>>>,
     // pub token_owners: MapView<u16, HashMap<u64, Owner>>,
     // pub token_publishers: MapView<u16, HashMap<u64, Owner>>,
+    /// Legacy token_id, collection_id, owner. Replaced by nft_owners
+    #[graphql(skip)]
     pub token_owners: MapView<u64, HashMap<u64, Owner>>,
     pub token_publishers: MapView<u64, HashMap<u64, Owner>>,
     pub credits_per_linera: RegisterView<Amount>,
@@ -28,6 +37,173 @@ pub struct Market {
     pub max_credits_percent: RegisterView<u8>,
     pub trade_fee_percent: RegisterView<u8>,
     pub avatars: MapView<Owner, Vec<u64>>,
+    /// Royalties earned by publisher from resales
+    pub publisher_royalties: MapView<Owner, Amount>,
+    #[graphql(skip)]
+    pub _auctions: MapView<u64, Auction>,
+    pub auction_id: RegisterView<u64>,
+    #[graphql(skip)]
+    pub _offers: MapView<u64, Offer>,
+    pub offer_id: RegisterView<u64>,
+    /// collection_id, token_id, owner
+    pub nft_owners: MapView<u64, HashMap<u16, Owner>>,
+    /// owner, collection_id, token_ids
+    pub owner_assets: MapView<Owner, HashMap<u64, BTreeSet<u16>>>,
+    pub ownership_migrated: RegisterView<bool>,
+    /// collection_id, token_id, events
+    #[graphql(skip)]
+    pub nft_histories: MapView<(u64, u16), Vec<NFTEvent>>,
+    /// Total sale amount of collection
+    pub collection_volumes: MapView<u64, Amount>,
+    /// collection_id, minter, minted count
+    pub mint_counts: MapView<u64, HashMap<Owner, u16>>,
+    /// Open offer ids indexed by expiry time
+    #[graphql(skip)]
+    pub offer_expiries: RegisterView<BTreeMap<Timestamp, Vec<u64>>>,
+    /// collection_id, token_id, open auction id
+    #[graphql(skip)]
+    pub open_auctions: MapView<(u64, u16), u64>,
+}
+
+/// Legacy token_owners entries migrated by each message
+const MIGRATION_BATCH: usize = 50;
+/// Page size of listings when first is not given
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+fn validate_metadata(metadata: &NFTMetadata) -> Result<(), MarketError> {
+    let mut trait_types = HashSet::new();
+    for nft_trait in &metadata.traits {
+        if nft_trait.trait_type.is_empty() || !trait_types.insert(&nft_trait.trait_type) {
+            return Err(MarketError::InvalidMetadata);
+        }
+        let valid = match nft_trait.value_type {
+            TraitValueType::String => !nft_trait.value.is_empty(),
+            TraitValueType::Number => nft_trait.value.parse::<f64>().is_ok(),
+            TraitValueType::Boolean => nft_trait.value.parse::<bool>().is_ok(),
+            TraitValueType::Date => nft_trait.value.parse::<u64>().is_ok(),
+        };
+        if !valid {
+            return Err(MarketError::InvalidMetadata);
+        }
+    }
+    Ok(())
+}
+
+/// Listing order key, ties are broken by collection and token id. Listings without price come
+/// first in ascending price order
+fn listing_key(order_by: Option<ListingOrder>, listing: &Listing) -> (u128, u64, u16) {
+    let price = listing
+        .price
+        .map_or(0, |price| u128::from(price).saturating_add(1));
+    let minted_at = listing.minted_at.micros() as u128;
+    let key = match order_by {
+        Some(ListingOrder::PriceAsc) => price,
+        Some(ListingOrder::PriceDesc) => u128::MAX - price,
+        Some(ListingOrder::Newest) => u128::MAX - minted_at,
+        Some(ListingOrder::Oldest) => minted_at,
+        None => 0,
+    };
+    (key, listing.collection_id, listing.token_id)
+}
+
+/// Page items in key order. Cursor is the encoded key of the last item of a page, paging resumes
+/// from the first key greater than it so it still works after that item is gone. Return the
+/// page and the cursor of the next page, none on the last page.
+pub fn paginate<K, T>(
+    mut items: Vec<(K, T)>,
+    after: Option<String>,
+    first: Option<usize>,
+) -> Result<(Vec<T>, Option<String>), serde_json::Error>
+where
+    K: Ord + Serialize + DeserializeOwned,
+{
+    let after = match after {
+        Some(after) => Some(serde_json::from_str::<K>(&after)?),
+        None => None,
+    };
+    let first = first.unwrap_or(DEFAULT_PAGE_SIZE);
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut page = Vec::new();
+    let mut has_more = false;
+    for (key, item) in items {
+        if after.as_ref().map_or(false, |after| key <= *after) {
+            continue;
+        }
+        if page.len() == first {
+            has_more = true;
+            break;
+        }
+        page.push((key, item));
+    }
+    let next_cursor = match page.last() {
+        Some((key, _)) if has_more => Some(serde_json::to_string(key)?),
+        _ => None,
+    };
+    Ok((
+        page.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+    ))
+}
+
+/// Dutch auction price decreases linearly from start price at creation to reserve at end
+fn dutch_price(auction: &Auction, now: Timestamp) -> Amount {
+    let duration = auction
+        .end_at
+        .micros()
+        .saturating_sub(auction.created_at.micros());
+    let elapsed = now.micros().saturating_sub(auction.created_at.micros());
+    if duration == 0 || elapsed >= duration {
+        return auction.reserve;
+    }
+    let range: u128 = auction.start_price.saturating_sub(auction.reserve).into();
+    auction.start_price.saturating_sub(Amount::from_attos(
+        range.saturating_mul(elapsed as u128) / duration as u128,
+    ))
+}
+
+/// Percent of amount, rounded down
+fn percent_of(amount: Amount, percent: u8) -> Amount {
+    Amount::from_attos(u128::from(amount).saturating_mul(percent as u128) / 100)
+}
+
+/// Royalty and trading fee together could not exceed the sale price
+fn validate_royalty(royalty_percent: u8, trade_fee_percent: u8) -> Result<(), MarketError> {
+    if royalty_percent > MAX_ROYALTY_PERCENT
+        || royalty_percent as u16 + trade_fee_percent as u16 > 100
+    {
+        return Err(MarketError::InvalidRoyalty);
+    }
+    Ok(())
+}
+
+/// Token value of credits is deducted from seller's tokens, capped by max credits percent of price
+fn plan_payment(
+    price: Amount,
+    fee: Amount,
+    publisher: Owner,
+    royalty: Amount,
+    credits: Amount,
+    discount: Amount,
+    max_credits_percent: u8,
+) -> Result<PaymentPlan, MarketError> {
+    if discount > percent_of(price, max_credits_percent) {
+        return Err(MarketError::CreditsExceedLimit);
+    }
+    let seller_tokens = price
+        .try_sub(fee)
+        .and_then(|amount| amount.try_sub(royalty))
+        .and_then(|amount| amount.try_sub(discount))
+        .map_err(|_| MarketError::CreditsExceedLimit)?;
+    Ok(PaymentPlan {
+        price,
+        credits,
+        discount,
+        tokens: seller_tokens.try_add(fee)?.try_add(royalty)?,
+        seller_tokens,
+        fee,
+        publisher,
+        royalty,
+    })
 }
 
 #[allow(dead_code)]
@@ -56,33 +232,31 @@ impl Market {
             .unwrap()
     }
 
-    pub(crate) async fn assets(&self, owner: Owner) -> HashMap<u64, Vec<u16>> {
-        self._assets.get(&owner).await.unwrap().unwrap()
+    pub(crate) async fn assets(&self, owner: Owner) -> Result<HashMap<u64, Vec<u16>>, MarketError> {
+        let mut assets = self.owner_assets.get(&owner).await?.unwrap_or_default();
+        for (collection_id, token_id, _owner) in self.legacy_owners().await? {
+            if _owner == owner {
+                assets.entry(collection_id).or_default().insert(token_id);
+            }
+        }
+        Ok(assets
+            .into_iter()
+            .map(|(collection_id, token_ids)| (collection_id, token_ids.into_iter().collect()))
+            .collect())
     }
 
+    /// Collection id is assigned here
     pub(crate) async fn create_collection(
         &mut self,
-        owner: Owner,
-        base_uri: String,
-        price: Option<Amount>,
-        name: String,
-        uris: Vec<String>,
-        now: Timestamp,
+        mut collection: Collection,
     ) -> Result<(), MarketError> {
-        if self.collection_uris.get().contains(&base_uri) {
+        if self.collection_uris.get().contains(&collection.base_uri) {
             return Err(MarketError::BaseURIALreadyExists);
         }
+        validate_royalty(collection.royalty_percent, *self.trade_fee_percent.get())?;
         let collection_id = *self.collection_id.get();
-        let collection = Collection {
-            collection_id,
-            base_uri,
-            uris,
-            price,
-            name,
-            nfts: HashMap::new(),
-            created_at: now,
-            publisher: owner,
-        };
+        collection.collection_id = collection_id;
+        let owner = collection.publisher;
         match self.publisher_collections.get(&owner).await {
             Ok(Some(mut collections)) => {
                 collections.push(collection.collection_id);
@@ -118,60 +292,64 @@ impl Market {
         }
     }
 
+    /// Token id is assigned here
     pub(crate) async fn mint_nft(
         &mut self,
         owner: Owner,
         collection_id: u64,
-        uri_index: u16,
-        price: Option<Amount>,
-        name: String,
-        now: Timestamp,
+        mut nft: NFT,
     ) -> Result<(), MarketError> {
-        self.validate_collection_owner(collection_id, owner).await?;
+        validate_metadata(&nft.metadata)?;
         match self._collections.get(&collection_id).await {
             Ok(Some(mut collection)) => {
-                if uri_index >= collection.uris.len() as u16 {
+                self.validate_minter(&collection, owner).await?;
+                if nft.uri_index >= collection.uris.len() as u16 {
                     return Err(MarketError::InvalidUriIndex);
                 }
-                if collection.price.is_none() && price.is_none() {
+                if collection.price.is_none() && nft.price.is_none() {
                     return Err(MarketError::InvalidPrice);
                 }
+                let collection_price = collection.price;
+                let publisher = collection.publisher;
+                let price = nft.price;
+                let now = nft.minted_at;
                 match self.token_ids.get(&collection_id).await {
                     Ok(Some(token_id)) => {
-                        collection.nfts.insert(
-                            token_id,
-                            NFT {
-                                token_id,
-                                uri_index,
-                                price,
-                                on_sale: true,
-                                minted_at: now,
-                                name,
-                            },
-                        );
+                        nft.token_id = token_id;
+                        collection.nfts.insert(token_id, nft);
                         self._collections.insert(&collection_id, collection)?;
                         self.token_ids.insert(&collection_id, token_id + 1)?;
+                        self.set_nft_owner(collection_id, token_id, owner).await?;
+                        self.add_owner_asset(owner, collection_id, token_id).await?;
+                        self.record_nft_event(
+                            collection_id,
+                            token_id,
+                            NFTEvent {
+                                kind: NFTEventKind::Mint,
+                                from: None,
+                                to: Some(owner),
+                                price: price.or(collection_price),
+                                created_at: now,
+                            },
+                        )
+                        .await?;
+                        let mut mint_counts = self
+                            .mint_counts
+                            .get(&collection_id)
+                            .await?
+                            .unwrap_or_default();
+                        *mint_counts.entry(owner).or_default() += 1;
+                        self.mint_counts.insert(&collection_id, mint_counts)?;
                         let token_id = token_id as u64;
-                        match self.token_owners.get(&token_id).await {
-                            Ok(Some(mut collection_owners)) => {
-                                collection_owners.insert(collection_id, owner);
-                                self.token_owners.insert(&token_id, collection_owners)?;
-                            }
-                            _ => {
-                                let mut collection_owners = HashMap::new();
-                                collection_owners.insert(collection_id, owner);
-                                self.token_owners.insert(&token_id, collection_owners)?;
-                            }
-                        }
                         match self.token_publishers.get(&token_id).await {
                             Ok(Some(mut collection_publisher)) => {
-                                collection_publisher.insert(collection_id, owner);
+                                collection_publisher.insert(collection_id, publisher);
                                 self.token_publishers
                                     .insert(&token_id, collection_publisher)?;
                             }
                             _ => {
                                 let mut collection_publisher = HashMap::new();
-                                collection_publisher.insert(collection_id, owner);
+                                collection_publisher.insert(collection_id, publisher);
                                 self.token_publishers
                                     .insert(&token_id, collection_publisher)?;
                             }
@@ -185,11 +363,275 @@ impl Market {
         Ok(())
     }
 
+    pub(crate) async fn collection(&self, collection_id: u64) -> Result<Collection, MarketError> {
+        match self._collections.get(&collection_id).await? {
+            Some(collection) => Ok(collection),
+            _ => Err(MarketError::CollectionNotExists),
+        }
+    }
+
+    async fn collection_listings(
+        &self,
+        collection: &Collection,
+    ) -> Result<Vec<Listing>, MarketError> {
+        let mut owners = self
+            .nft_owners
+            .get(&collection.collection_id)
+            .await?
+            .unwrap_or_default();
+        for (collection_id, token_id, owner) in self.legacy_owners().await? {
+            if collection_id == collection.collection_id {
+                owners.insert(token_id, owner);
+            }
+        }
+        let mut listings = Vec::new();
+        for nft in collection.nfts.values() {
+            let Some(owner) = owners.get(&nft.token_id) else {
+                continue;
+            };
+            listings.push(Listing {
+                collection_id: collection.collection_id,
+                collection_name: collection.name.clone(),
+                base_uri: collection.base_uri.clone(),
+                uri: collection
+                    .uris
+                    .get(nft.uri_index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                token_id: nft.token_id,
+                name: nft.name.clone(),
+                owner: *owner,
+                price: nft.price.or(collection.price),
+                on_sale: nft.on_sale,
+                minted_at: nft.minted_at,
+                metadata: nft.metadata.clone(),
+            });
+        }
+        listings.sort_by_key(|listing| listing.token_id);
+        Ok(listings)
+    }
+
+    pub(crate) async fn listings(
+        &self,
+        filter: ListingFilter,
+        order_by: Option<ListingOrder>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Listing>, MarketError> {
+        let ListingFilter {
+            on_sale,
+            min_price,
+            max_price,
+            collection_id,
+            traits,
+        } = filter;
+        let collection_ids = match collection_id {
+            Some(collection_id) => vec![collection_id],
+            None => self._collections.indices().await?,
+        };
+        let mut listings = Vec::new();
+        for collection_id in collection_ids {
+            let collection = self.collection(collection_id).await?;
+            listings.extend(
+                self.collection_listings(&collection)
+                    .await?
+                    .into_iter()
+                    .filter(|listing| {
+                        on_sale.map_or(true, |on_sale| listing.on_sale == on_sale)
+                            && min_price
+                                .map_or(true, |min| listing.price.map_or(false, |p| p >= min))
+                            && max_price
+                                .map_or(true, |max| listing.price.map_or(false, |p| p <= max))
+                            && traits.iter().all(|filter| {
+                                listing.metadata.traits.iter().any(|nft_trait| {
+                                    nft_trait.trait_type == filter.trait_type
+                                        && nft_trait.value == filter.value
+                                })
+                            })
+                    }),
+            );
+        }
+        let listings = listings
+            .into_iter()
+            .map(|listing| (listing_key(order_by, &listing), listing))
+            .collect();
+        let (items, next_cursor) = paginate(listings, after, first)?;
+        Ok(Page { items, next_cursor })
+    }
+
+    pub(crate) async fn collection_summary(
+        &self,
+        collection_id: u64,
+    ) -> Result<CollectionSummary, MarketError> {
+        let collection = self.collection(collection_id).await?;
+        let listings = self.collection_listings(&collection).await?;
+        let floor_price = listings
+            .iter()
+            .filter(|listing| listing.on_sale)
+            .filter_map(|listing| listing.price)
+            .min();
+        let owner_count = listings
+            .iter()
+            .map(|listing| listing.owner)
+            .collect::<HashSet<_>>()
+            .len() as u32;
+        Ok(CollectionSummary {
+            floor_price,
+            owner_count,
+            supply: listings.len() as u32,
+            volume: self.collection_volume(collection_id).await?,
+            collection,
+        })
+    }
+
+    pub(crate) async fn trait_rarities(
+        &self,
+        collection_id: u64,
+    ) -> Result<Vec<TraitRarity>, MarketError> {
+        let collection = self.collection(collection_id).await?;
+        let mut counts = BTreeMap::<(String, String), u32>::new();
+        for nft in collection.nfts.values() {
+            for nft_trait in &nft.metadata.traits {
+                *counts
+                    .entry((nft_trait.trait_type.clone(), nft_trait.value.clone()))
+                    .or_default() += 1;
+            }
+        }
+        let supply = collection.nfts.len().max(1) as u64;
+        Ok(counts
+            .into_iter()
+            .map(|((trait_type, value), count)| TraitRarity {
+                trait_type,
+                value,
+                count,
+                rarity: (count as u64 * 10000 / supply) as u16,
+            })
+            .collect())
+    }
+
+    pub(crate) async fn owned_by(&self, owner: Owner) -> Result<Vec<Listing>, MarketError> {
+        let mut listings = Vec::new();
+        for (collection_id, token_ids) in self.assets(owner).await? {
+            let collection = self.collection(collection_id).await?;
+            listings.extend(
+                self.collection_listings(&collection)
+                    .await?
+                    .into_iter()
+                    .filter(|listing| token_ids.contains(&listing.token_id)),
+            );
+        }
+        listings.sort_by_key(|listing| (listing.collection_id, listing.token_id));
+        Ok(listings)
+    }
+
+    pub(crate) async fn set_mint_policy(
+        &mut self,
+        owner: Owner,
+        collection_id: u64,
+        policy: MintPolicy,
+    ) -> Result<(), MarketError> {
+        let mut collection = self.collection(collection_id).await?;
+        if collection.publisher != owner {
+            return Err(MarketError::NotCollectionOwner);
+        }
+        if policy.max_supply.map_or(false, |max_supply| {
+            (max_supply as usize) < collection.nfts.len()
+        }) {
+            return Err(MarketError::InvalidMintPolicy);
+        }
+        if let (Some(start_at), Some(end_at)) = (policy.start_at, policy.end_at) {
+            if start_at >= end_at {
+                return Err(MarketError::InvalidMintPolicy);
+            }
+        }
+        collection.mint_policy = policy;
+        self._collections.insert(&collection_id, collection)?;
+        Ok(())
+    }
+
+    pub(crate) async fn mint_count(
+        &self,
+        collection_id: u64,
+        minter: Owner,
+    ) -> Result<u16, MarketError> {
+        Ok(self
+            .mint_counts
+            .get(&collection_id)
+            .await?
+            .and_then(|counts| counts.get(&minter).copied())
+            .unwrap_or_default())
+    }
+
+    pub(crate) async fn validate_minter(
+        &self,
+        collection: &Collection,
+        minter: Owner,
+    ) -> Result<(), MarketError> {
+        let policy = &collection.mint_policy;
+        if policy.max_supply.map_or(false, |max_supply| {
+            collection.nfts.len() >= max_supply as usize
+        }) {
+            return Err(MarketError::SupplyExceeded);
+        }
+        if collection.publisher == minter {
+            return Ok(());
+        }
+        if !policy.public && !policy.allowlist.contains(&minter) {
+            return Err(MarketError::MintNotAllowed);
+        }
+        if collection.price.is_none() {
+            return Err(MarketError::InvalidPrice);
+        }
+        if let Some(wallet_limit) = policy.wallet_limit {
+            if self.mint_count(collection.collection_id, minter).await? >= wallet_limit {
+                return Err(MarketError::MintLimitExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Block time differs between chains, so only the creation chain checks mint window
+    pub(crate) async fn validate_mint_time(
+        &self,
+        collection_id: u64,
+        minter: Owner,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        let collection = self.collection(collection_id).await?;
+        if collection.publisher == minter {
+            return Ok(());
+        }
+        let policy = &collection.mint_policy;
+        if policy.start_at.map_or(false, |start_at| now < start_at)
+            || policy.end_at.map_or(false, |end_at| now >= end_at)
+        {
+            return Err(MarketError::MintWindowClosed);
+        }
+        Ok(())
+    }
+
+    /// Publisher and collection price paid by minter, publisher mints for free
+    pub(crate) async fn mint_payment(
+        &self,
+        collection_id: u64,
+        minter: Owner,
+    ) -> Result<Option<(Owner, Amount)>, MarketError> {
+        let collection = self.collection(collection_id).await?;
+        if collection.publisher == minter {
+            return Ok(None);
+        }
+        match collection.price {
+            Some(price) => Ok(Some((collection.publisher, price))),
+            _ => Err(MarketError::InvalidPrice),
+        }
+    }
+
     pub(crate) async fn buy_nft(
         &mut self,
         buyer: Owner,
         collection_id: u64,
         token_id: u16,
+        now: Timestamp,
     ) -> Result<(), MarketError> {
         match self._collections.get(&collection_id).await {
             Ok(Some(collection)) => match collection.nfts.get(&token_id) {
@@ -197,47 +639,13 @@ impl Market {
                     if !nft.on_sale {
                         return Err(MarketError::TokenNotOnSale);
                     }
-                    let token_id = token_id as u64;
-                    let token_owners = match self.token_owners.get(&token_id).await {
-                        Ok(Some(owners)) => owners,
-                        _ => HashMap::default(),
-                    };
-                    let owner = match token_owners.get(&collection_id) {
-                        Some(owner) => *owner,
-                        _ => return Err(MarketError::CollectionNotExists),
-                    };
+                    let owner = self.nft_owner(collection_id, token_id).await?;
                     if owner == buyer {
                         return Err(MarketError::BuyerIsOwner);
                     }
-                    let mut token_owners = token_owners.clone();
-                    token_owners.insert(collection_id, buyer);
-                    self.token_owners.insert(&token_id, token_owners)?;
-                    match self._assets.get(&owner).await {
-                        Ok(Some(collections)) => {
-                            let collections = collections.clone();
-                            let token_id = token_id as u16;
-                            match collections.get(&collection_id) {
-                                Some(token_ids) => {
-                                    let mut token_ids = token_ids.clone();
-                                    token_ids.push(token_id);
-                                    let mut collections = collections.clone();
-                                    collections.insert(collection_id, token_ids);
-                                    self._assets.insert(&owner, collections)?;
-                                }
-                                None => {
-                                    let mut collections = collections.clone();
-                                    collections.insert(collection_id, vec![token_id]);
-                                    self._assets.insert(&owner, collections)?;
-                                }
-                            }
-                        }
-                        _ => {
-                            let mut collections = HashMap::default();
-                            let token_id = token_id as u16;
-                            collections.insert(collection_id, vec![token_id]);
-                            self._assets.insert(&owner, collections)?;
-                        }
-                    }
+                    let price = self.nft_price(collection_id, token_id).await?;
+                    self.record_sale(collection_id, token_id, owner, buyer, price, now)
+                        .await?;
                 }
                 _ => return Err(MarketError::TokenIDNotExists),
             },
@@ -246,22 +654,281 @@ impl Market {
         Ok(())
     }
 
+    pub(crate) async fn record_royalty(
+        &mut self,
+        collection_id: u64,
+        seller: Owner,
+        price: Amount,
+    ) -> Result<(), MarketError> {
+        let (publisher, royalty) = self.royalty(collection_id, seller, price).await?;
+        if royalty > Amount::ZERO {
+            let earned = self.royalties(publisher).await?;
+            self.publisher_royalties
+                .insert(&publisher, earned.saturating_add(royalty))?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn change_owner(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        owner: Owner,
+        buyer: Owner,
+    ) -> Result<(), MarketError> {
+        self.set_nft_owner(collection_id, token_id, buyer).await?;
+        if self.avatars.get(&owner).await? == Some(vec![collection_id, token_id as u64]) {
+            self.avatars.remove(&owner)?;
+        }
+        self.remove_owner_asset(owner, collection_id, token_id)
+            .await?;
+        self.add_owner_asset(buyer, collection_id, token_id).await
+    }
+
+    /// Gift NFT to another user, it is taken off sale
+    pub(crate) async fn transfer_nft(
+        &mut self,
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+        to: Owner,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        if self.nft_owner(collection_id, token_id).await? != owner {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if to == owner {
+            return Err(MarketError::InvalidReceiver);
+        }
+        if self.open_auction(collection_id, token_id).await?.is_some() {
+            return Err(MarketError::NFTInAuction);
+        }
+        self.off_sale_nft(owner, collection_id, token_id).await?;
+        self.change_owner(collection_id, token_id, owner, to)
+            .await?;
+        self.record_nft_event(
+            collection_id,
+            token_id,
+            NFTEvent {
+                kind: NFTEventKind::Transfer,
+                from: Some(owner),
+                to: Some(to),
+                price: None,
+                created_at: now,
+            },
+        )
+        .await
+    }
+
+    /// Sold NFT is taken off sale, the buyer lists it again with a new price
+    pub(crate) async fn record_sale(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        seller: Owner,
+        buyer: Owner,
+        price: Amount,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        self.record_royalty(collection_id, seller, price).await?;
+        self.off_sale_nft(seller, collection_id, token_id).await?;
+        self.change_owner(collection_id, token_id, seller, buyer)
+            .await?;
+        let volume = self.collection_volume(collection_id).await?;
+        self.collection_volumes
+            .insert(&collection_id, volume.saturating_add(price))?;
+        self.record_nft_event(
+            collection_id,
+            token_id,
+            NFTEvent {
+                kind: NFTEventKind::Sale,
+                from: Some(seller),
+                to: Some(buyer),
+                price: Some(price),
+                created_at: now,
+            },
+        )
+        .await
+    }
+
+    pub(crate) async fn record_nft_event(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        event: NFTEvent,
+    ) -> Result<(), MarketError> {
+        let mut events = self.nft_history(collection_id, token_id).await?;
+        events.push(event);
+        self.nft_histories
+            .insert(&(collection_id, token_id), events)?;
+        Ok(())
+    }
+
+    pub(crate) async fn nft_history(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<Vec<NFTEvent>, MarketError> {
+        Ok(self
+            .nft_histories
+            .get(&(collection_id, token_id))
+            .await?
+            .unwrap_or_default())
+    }
+
+    pub(crate) async fn collection_volume(
+        &self,
+        collection_id: u64,
+    ) -> Result<Amount, MarketError> {
+        Ok(self
+            .collection_volumes
+            .get(&collection_id)
+            .await?
+            .unwrap_or_default())
+    }
+
+    pub(crate) async fn set_nft_owner(
+        &mut self,
+        collection_id: u64,
+        token_id: u16,
+        owner: Owner,
+    ) -> Result<(), MarketError> {
+        let mut owners = self
+            .nft_owners
+            .get(&collection_id)
+            .await?
+            .unwrap_or_default();
+        owners.insert(token_id, owner);
+        self.nft_owners.insert(&collection_id, owners)?;
+        if *self.ownership_migrated.get() {
+            return Ok(());
+        }
+        // Legacy entry is stale once the token is in the ownership index
+        let legacy_token_id = token_id as u64;
+        if let Some(mut legacy_owners) = self.token_owners.get(&legacy_token_id).await? {
+            legacy_owners.remove(&collection_id);
+            if legacy_owners.is_empty() {
+                self.token_owners.remove(&legacy_token_id)?;
+            } else {
+                self.token_owners.insert(&legacy_token_id, legacy_owners)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn add_owner_asset(
+        &mut self,
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), MarketError> {
+        let mut assets = self.owner_assets.get(&owner).await?.unwrap_or_default();
+        assets.entry(collection_id).or_default().insert(token_id);
+        self.owner_assets.insert(&owner, assets)?;
+        Ok(())
+    }
+
+    pub(crate) async fn remove_owner_asset(
+        &mut self,
+        owner: Owner,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<(), MarketError> {
+        let mut assets = self.owner_assets.get(&owner).await?.unwrap_or_default();
+        if let Some(token_ids) = assets.get_mut(&collection_id) {
+            token_ids.remove(&token_id);
+            if token_ids.is_empty() {
+                assets.remove(&collection_id);
+            }
+        }
+        if assets.is_empty() {
+            self.owner_assets.remove(&owner)?;
+        } else {
+            self.owner_assets.insert(&owner, assets)?;
+        }
+        Ok(())
+    }
+
+    /// Collection, token and owner of legacy token_owners entries not migrated yet
+    async fn legacy_owners(&self) -> Result<Vec<(u64, u16, Owner)>, MarketError> {
+        let mut owners = Vec::new();
+        if *self.ownership_migrated.get() {
+            return Ok(owners);
+        }
+        for token_id in self.token_owners.indices().await? {
+            let token_owners = self.token_owners.get(&token_id).await?.unwrap_or_default();
+            for (collection_id, owner) in token_owners {
+                owners.push((collection_id, token_id as u16, owner));
+            }
+        }
+        Ok(owners)
+    }
+
+    /// Move a batch of legacy token_owners entries to the ownership index, queries fall back to
+    /// the rest until done. Legacy _assets is dropped then since sold tokens were recorded to
+    /// seller there
+    pub(crate) async fn migrate_ownership(&mut self) -> Result<(), MarketError> {
+        if *self.ownership_migrated.get() {
+            return Ok(());
+        }
+        let mut token_ids = Vec::new();
+        self.token_owners
+            .for_each_index_while(|token_id| {
+                token_ids.push(token_id);
+                Ok(token_ids.len() < MIGRATION_BATCH)
+            })
+            .await?;
+        for token_id in token_ids.iter() {
+            let token_owners = self.token_owners.get(token_id).await?.unwrap_or_default();
+            for (collection_id, owner) in token_owners {
+                self.set_nft_owner(collection_id, *token_id as u16, owner)
+                    .await?;
+                self.add_owner_asset(owner, collection_id, *token_id as u16)
+                    .await?;
+            }
+            self.token_owners.remove(token_id)?;
+        }
+        if token_ids.len() < MIGRATION_BATCH {
+            self._assets.clear();
+            self.ownership_migrated.set(true);
+        }
+        Ok(())
+    }
+
     pub(crate) async fn nft_owner(
         &self,
         collection_id: u64,
         token_id: u16,
     ) -> Result<Owner, MarketError> {
-        let token_id = token_id as u64;
-        let token_owners = match self.token_owners.get(&token_id).await {
-            Ok(Some(owners)) => owners,
-            _ => HashMap::default(),
-        };
-        match token_owners.get(&collection_id) {
-            Some(owner) => Ok(*owner),
-            _ => Err(MarketError::NotCollectionOwner),
+        match self
+            .nft_owners
+            .get(&collection_id)
+            .await?
+            .and_then(|owners| owners.get(&token_id).copied())
+        {
+            Some(owner) => Ok(owner),
+            _ => match self.legacy_nft_owner(collection_id, token_id).await? {
+                Some(owner) => Ok(owner),
+                _ => Err(MarketError::TokenIDNotExists),
+            },
         }
     }
 
+    async fn legacy_nft_owner(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<Option<Owner>, MarketError> {
+        if *self.ownership_migrated.get() {
+            return Ok(None);
+        }
+        Ok(self
+            .token_owners
+            .get(&(token_id as u64))
+            .await?
+            .and_then(|owners| owners.get(&collection_id).copied()))
+    }
+
     pub(crate) async fn nft_price(
         &self,
         collection_id: u64,
@@ -288,6 +955,7 @@ impl Market {
         collection_id: u64,
         token_id: Option<u16>,
         price: Amount,
+        now: Timestamp,
     ) -> Result<(), MarketError> {
         match token_id {
             Some(token_id) => {
@@ -300,7 +968,19 @@ impl Market {
                             let mut _nft = nft.clone();
                             _nft.price = Some(price);
                             collection.nfts.insert(nft.token_id, _nft);
-                            self._collections.insert(&collection_id, collection)?
+                            self._collections.insert(&collection_id, collection)?;
+                            self.record_nft_event(
+                                collection_id,
+                                token_id,
+                                NFTEvent {
+                                    kind: NFTEventKind::PriceChange,
+                                    from: Some(owner),
+                                    to: None,
+                                    price: Some(price),
+                                    created_at: now,
+                                },
+                            )
+                            .await?;
                         }
                         _ => return Err(MarketError::TokenIDNotExists),
                     },
@@ -318,7 +998,29 @@ impl Market {
                 match self._collections.get(&collection_id).await {
                     Ok(Some(mut collection)) => {
                         collection.price = Some(price);
-                        self._collections.insert(&collection_id, collection)?
+                        // NFTs without own price follow collection price
+                        let token_ids = collection
+                            .nfts
+                            .values()
+                            .filter(|nft| nft.price.is_none())
+                            .map(|nft| nft.token_id)
+                            .collect::<Vec<_>>();
+                        self._collections.insert(&collection_id, collection)?;
+                        for token_id in token_ids {
+                            let owner = self.nft_owner(collection_id, token_id).await?;
+                            self.record_nft_event(
+                                collection_id,
+                                token_id,
+                                NFTEvent {
+                                    kind: NFTEventKind::PriceChange,
+                                    from: Some(owner),
+                                    to: None,
+                                    price: Some(price),
+                                    created_at: now,
+                                },
+                            )
+                            .await?;
+                        }
                     }
                     _ => return Err(MarketError::CollectionNotExists),
                 }
@@ -336,6 +1038,9 @@ impl Market {
         if self.nft_owner(collection_id, token_id).await.unwrap() != owner {
             return Err(MarketError::NotTokenOwner);
         }
+        if self.open_auction(collection_id, token_id).await?.is_some() {
+            return Err(MarketError::NFTInAuction);
+        }
         match self._collections.get(&collection_id).await {
             Ok(Some(mut collection)) => match collection.nfts.get(&token_id) {
                 Some(nft) => {
@@ -399,14 +1104,438 @@ impl Market {
         }
     }
 
-    pub(crate) async fn trading_fee(&self, amount: Amount) -> Result<Amount, MarketError> {
-        Ok(Amount::from_attos(
-            Amount::from_attos(*self.trade_fee_percent.get() as u128)
-                .saturating_mul(amount.into())
-                .saturating_div(Amount::from_attos(100 as u128)),
+    /// Publisher and royalty paid to it, no royalty if publisher sells its own NFT
+    pub(crate) async fn royalty(
+        &self,
+        collection_id: u64,
+        seller: Owner,
+        price: Amount,
+    ) -> Result<(Owner, Amount), MarketError> {
+        let collection = match self._collections.get(&collection_id).await? {
+            Some(collection) => collection,
+            _ => return Err(MarketError::CollectionNotExists),
+        };
+        if collection.publisher == seller {
+            return Ok((collection.publisher, Amount::ZERO));
+        }
+        Ok((
+            collection.publisher,
+            percent_of(price, collection.royalty_percent),
         ))
     }
 
+    pub(crate) async fn royalties(&self, publisher: Owner) -> Result<Amount, MarketError> {
+        Ok(self
+            .publisher_royalties
+            .get(&publisher)
+            .await?
+            .unwrap_or_default())
+    }
+
+    pub(crate) async fn auction(&self, auction_id: u64) -> Result<Auction, MarketError> {
+        match self._auctions.get(&auction_id).await? {
+            Some(auction) => Ok(auction),
+            _ => Err(MarketError::InvalidAuction),
+        }
+    }
+
+    pub(crate) async fn auctions(
+        &self,
+        status: Option<AuctionStatus>,
+        collection_id: Option<u64>,
+    ) -> Result<Vec<Auction>, MarketError> {
+        let mut auctions = Vec::new();
+        for auction_id in self._auctions.indices().await? {
+            let auction = self.auction(auction_id).await?;
+            if status.map_or(true, |status| auction.status == status)
+                && collection_id
+                    .map_or(true, |collection_id| auction.collection_id == collection_id)
+            {
+                auctions.push(auction);
+            }
+        }
+        Ok(auctions)
+    }
+
+    pub(crate) async fn open_auction(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+    ) -> Result<Option<Auction>, MarketError> {
+        match self.open_auctions.get(&(collection_id, token_id)).await? {
+            Some(auction_id) => Ok(Some(self.auction(auction_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// NFT is taken off sale while in auction, auction id is assigned here
+    pub(crate) async fn create_auction(
+        &mut self,
+        mut auction: Auction,
+    ) -> Result<u64, MarketError> {
+        let (seller, collection_id, token_id) =
+            (auction.seller, auction.collection_id, auction.token_id);
+        if self.nft_owner(collection_id, token_id).await? != seller {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if self.open_auction(collection_id, token_id).await?.is_some() {
+            return Err(MarketError::NFTInAuction);
+        }
+        if auction.start_price == Amount::ZERO
+            || (auction.kind == AuctionKind::Dutch && auction.reserve > auction.start_price)
+        {
+            return Err(MarketError::InvalidAuction);
+        }
+        self.off_sale_nft(seller, collection_id, token_id).await?;
+        let auction_id = *self.auction_id.get();
+        auction.auction_id = auction_id;
+        self._auctions.insert(&auction_id, auction)?;
+        self.open_auctions
+            .insert(&(collection_id, token_id), auction_id)?;
+        self.auction_id.set(auction_id + 1);
+        Ok(auction_id)
+    }
+
+    /// Block time differs between chains, so only the creation chain checks time
+    pub(crate) async fn validate_bid_time(
+        &self,
+        auction_id: u64,
+        amount: Amount,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        let auction = self.auction(auction_id).await?;
+        if now >= auction.end_at {
+            return Err(MarketError::AuctionEnded);
+        }
+        if auction.kind == AuctionKind::Dutch && amount < dutch_price(&auction, now) {
+            return Err(MarketError::BidTooLow);
+        }
+        Ok(())
+    }
+
+    /// Return the outbid bid to be refunded, Dutch auction is sold to the first bid
+    pub(crate) async fn bid(
+        &mut self,
+        bidder: Owner,
+        auction_id: u64,
+        amount: Amount,
+        now: Timestamp,
+    ) -> Result<Option<AuctionBid>, MarketError> {
+        let mut auction = self.auction(auction_id).await?;
+        if auction.status != AuctionStatus::Open {
+            return Err(MarketError::AuctionNotOpen);
+        }
+        if auction.seller == bidder {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        let bid = AuctionBid {
+            bidder,
+            amount,
+            created_at: now,
+        };
+        let outbid = match auction.kind {
+            AuctionKind::English => {
+                let too_low = match &auction.highest_bid {
+                    Some(highest_bid) => amount <= highest_bid.amount,
+                    None => amount < auction.start_price,
+                };
+                if too_low {
+                    return Err(MarketError::BidTooLow);
+                }
+                auction.highest_bid.replace(bid)
+            }
+            AuctionKind::Dutch => {
+                self.record_sale(
+                    auction.collection_id,
+                    auction.token_id,
+                    auction.seller,
+                    bidder,
+                    amount,
+                    now,
+                )
+                .await?;
+                auction.highest_bid = Some(bid);
+                auction.status = AuctionStatus::Sold;
+                None
+            }
+        };
+        if auction.status != AuctionStatus::Open {
+            self.open_auctions
+                .remove(&(auction.collection_id, auction.token_id))?;
+        }
+        self._auctions.insert(&auction_id, auction)?;
+        Ok(outbid)
+    }
+
+    /// Only auction without bid could be cancelled
+    pub(crate) async fn cancel_auction(
+        &mut self,
+        seller: Owner,
+        auction_id: u64,
+    ) -> Result<(), MarketError> {
+        let mut auction = self.auction(auction_id).await?;
+        if auction.seller != seller {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if auction.status != AuctionStatus::Open {
+            return Err(MarketError::AuctionNotOpen);
+        }
+        if auction.highest_bid.is_some() {
+            return Err(MarketError::AuctionHasBids);
+        }
+        auction.status = AuctionStatus::Cancelled;
+        self.open_auctions
+            .remove(&(auction.collection_id, auction.token_id))?;
+        self._auctions.insert(&auction_id, auction)?;
+        Ok(())
+    }
+
+    pub(crate) async fn validate_settle_time(
+        &self,
+        auction_id: u64,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        if now < self.auction(auction_id).await?.end_at {
+            return Err(MarketError::AuctionNotEnded);
+        }
+        Ok(())
+    }
+
+    /// Highest bid reaching reserve wins, otherwise the auction is unsold
+    pub(crate) async fn settle_auction(
+        &mut self,
+        auction_id: u64,
+        now: Timestamp,
+    ) -> Result<Auction, MarketError> {
+        let mut auction = self.auction(auction_id).await?;
+        if auction.status != AuctionStatus::Open {
+            return Err(MarketError::AuctionNotOpen);
+        }
+        match auction.highest_bid.clone() {
+            Some(bid) if bid.amount >= auction.reserve => {
+                self.record_sale(
+                    auction.collection_id,
+                    auction.token_id,
+                    auction.seller,
+                    bid.bidder,
+                    bid.amount,
+                    now,
+                )
+                .await?;
+                auction.status = AuctionStatus::Sold;
+            }
+            _ => auction.status = AuctionStatus::Unsold,
+        }
+        self.open_auctions
+            .remove(&(auction.collection_id, auction.token_id))?;
+        self._auctions.insert(&auction_id, auction.clone())?;
+        Ok(auction)
+    }
+
+    pub(crate) async fn offer(&self, offer_id: u64) -> Result<Offer, MarketError> {
+        match self._offers.get(&offer_id).await? {
+            Some(offer) => Ok(offer),
+            _ => Err(MarketError::InvalidOffer),
+        }
+    }
+
+    pub(crate) async fn offers(
+        &self,
+        status: Option<OfferStatus>,
+        collection_id: Option<u64>,
+        token_id: Option<u16>,
+        buyer: Option<Owner>,
+    ) -> Result<Vec<Offer>, MarketError> {
+        let mut offers = Vec::new();
+        for offer_id in self._offers.indices().await? {
+            let offer = self.offer(offer_id).await?;
+            if status.map_or(true, |status| offer.status == status)
+                && collection_id.map_or(true, |collection_id| offer.collection_id == collection_id)
+                && token_id.map_or(true, |token_id| offer.token_id == token_id)
+                && buyer.map_or(true, |buyer| offer.buyer == buyer)
+            {
+                offers.push(offer);
+            }
+        }
+        Ok(offers)
+    }
+
+    pub(crate) async fn make_offer(
+        &mut self,
+        buyer: Owner,
+        collection_id: u64,
+        token_id: u16,
+        amount: Amount,
+        expires_at: Timestamp,
+        now: Timestamp,
+    ) -> Result<u64, MarketError> {
+        if self.nft_owner(collection_id, token_id).await? == buyer {
+            return Err(MarketError::BuyerIsOwner);
+        }
+        if amount == Amount::ZERO {
+            return Err(MarketError::InvalidPrice);
+        }
+        let offer_id = *self.offer_id.get();
+        self._offers.insert(
+            &offer_id,
+            Offer {
+                offer_id,
+                collection_id,
+                token_id,
+                buyer,
+                amount,
+                created_at: now,
+                expires_at,
+                status: OfferStatus::Open,
+            },
+        )?;
+        self.offer_expiries
+            .get_mut()
+            .entry(expires_at)
+            .or_default()
+            .push(offer_id);
+        self.offer_id.set(offer_id + 1);
+        Ok(offer_id)
+    }
+
+    /// Block time differs between chains, so only the creation chain checks expiry
+    pub(crate) async fn validate_offer_time(
+        &self,
+        offer_id: u64,
+        now: Timestamp,
+    ) -> Result<(), MarketError> {
+        if now >= self.offer(offer_id).await?.expires_at {
+            return Err(MarketError::OfferExpired);
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn accept_offer(
+        &mut self,
+        owner: Owner,
+        offer_id: u64,
+        now: Timestamp,
+    ) -> Result<Offer, MarketError> {
+        let mut offer = self.offer(offer_id).await?;
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        if self.nft_owner(offer.collection_id, offer.token_id).await? != owner {
+            return Err(MarketError::NotTokenOwner);
+        }
+        if self
+            .open_auction(offer.collection_id, offer.token_id)
+            .await?
+            .is_some()
+        {
+            return Err(MarketError::NFTInAuction);
+        }
+        self.record_sale(
+            offer.collection_id,
+            offer.token_id,
+            owner,
+            offer.buyer,
+            offer.amount,
+            now,
+        )
+        .await?;
+        offer.status = OfferStatus::Accepted;
+        self.close_offer(&offer);
+        self._offers.insert(&offer_id, offer.clone())?;
+        Ok(offer)
+    }
+
+    pub(crate) async fn cancel_offer(
+        &mut self,
+        buyer: Owner,
+        offer_id: u64,
+    ) -> Result<Offer, MarketError> {
+        let mut offer = self.offer(offer_id).await?;
+        if offer.buyer != buyer {
+            return Err(MarketError::NotOfferOwner);
+        }
+        if offer.status != OfferStatus::Open {
+            return Err(MarketError::OfferNotOpen);
+        }
+        offer.status = OfferStatus::Cancelled;
+        self.close_offer(&offer);
+        self._offers.insert(&offer_id, offer.clone())?;
+        Ok(offer)
+    }
+
+    /// Drop the offer from expiry index once it's not open
+    fn close_offer(&mut self, offer: &Offer) {
+        let index = self.offer_expiries.get_mut();
+        if let Some(offer_ids) = index.get_mut(&offer.expires_at) {
+            offer_ids.retain(|offer_id| *offer_id != offer.offer_id);
+            if offer_ids.is_empty() {
+                index.remove(&offer.expires_at);
+            }
+        }
+    }
+
+    /// At most limit open offers expired at now, earliest first
+    pub(crate) fn expired_offers(&self, now: Timestamp, limit: usize) -> Vec<u64> {
+        self.offer_expiries
+            .get()
+            .range(..=now)
+            .flat_map(|(_, offer_ids)| offer_ids.iter().copied())
+            .take(limit)
+            .collect()
+    }
+
+    /// Return offers expired by this call to be refunded
+    pub(crate) async fn expire_offers(
+        &mut self,
+        offer_ids: Vec<u64>,
+    ) -> Result<Vec<Offer>, MarketError> {
+        let mut expired = Vec::new();
+        for offer_id in offer_ids {
+            let mut offer = self.offer(offer_id).await?;
+            if offer.status != OfferStatus::Open {
+                continue;
+            }
+            offer.status = OfferStatus::Expired;
+            self.close_offer(&offer);
+            self._offers.insert(&offer_id, offer.clone())?;
+            expired.push(offer);
+        }
+        Ok(expired)
+    }
+
+    pub(crate) async fn trading_fee(&self, amount: Amount) -> Result<Amount, MarketError> {
+        Ok(percent_of(amount, *self.trade_fee_percent.get()))
+    }
+
+    /// Credits are paid to seller as part of price, fee and royalty are always paid with tokens
+    pub(crate) async fn payment_plan(
+        &self,
+        collection_id: u64,
+        token_id: u16,
+        credits: Amount,
+    ) -> Result<PaymentPlan, MarketError> {
+        let owner = self.nft_owner(collection_id, token_id).await?;
+        let price = self.nft_price(collection_id, token_id).await?;
+        let fee = self.trading_fee(price).await?;
+        let (publisher, royalty) = self.royalty(collection_id, owner, price).await?;
+        let discount = if credits == Amount::ZERO {
+            Amount::ZERO
+        } else if *self.credits_per_linera.get() == Amount::ZERO {
+            return Err(MarketError::CreditsExceedLimit);
+        } else {
+            self.credits_to_tokens(credits).await?
+        };
+        plan_payment(
+            price,
+            fee,
+            publisher,
+            royalty,
+            credits,
+            discount,
+            *self.max_credits_percent.get(),
+        )
+    }
+
     pub(crate) async fn credits_to_tokens(&self, credits: Amount) -> Result<Amount, MarketError> {
         Ok(Amount::from_attos(
             credits
@@ -415,3 +1544,281 @@ impl Market {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use linera_sdk::base::{Amount, Owner, Timestamp};
+    use market::{
+        Auction, AuctionKind, AuctionStatus, Listing, ListingOrder, NFTMetadata, NFTTrait,
+        TraitValueType, MAX_ROYALTY_PERCENT,
+    };
+
+    use super::{
+        dutch_price, listing_key, paginate, percent_of, plan_payment, validate_metadata,
+        validate_royalty,
+    };
+
+    fn owner(index: u8) -> Owner {
+        Owner::from_str(&format!("{:064x}", index)).unwrap()
+    }
+
+    fn dutch_auction(start_price: u128, reserve: u128, created_at: u64, end_at: u64) -> Auction {
+        Auction {
+            auction_id: 0,
+            collection_id: 1000,
+            token_id: 1000,
+            seller: owner(1),
+            kind: AuctionKind::Dutch,
+            start_price: Amount::from_tokens(start_price),
+            reserve: Amount::from_tokens(reserve),
+            created_at: Timestamp::from(created_at),
+            end_at: Timestamp::from(end_at),
+            highest_bid: None,
+            status: AuctionStatus::Open,
+        }
+    }
+
+    #[test]
+    fn percent_rounds_down() {
+        assert_eq!(
+            percent_of(Amount::from_tokens(10), 5),
+            Amount::from_millis(500)
+        );
+        assert_eq!(percent_of(Amount::from_attos(99), 1), Amount::ZERO);
+        assert_eq!(percent_of(Amount::from_tokens(3), 0), Amount::ZERO);
+        assert_eq!(
+            percent_of(Amount::from_tokens(3), 100),
+            Amount::from_tokens(3)
+        );
+    }
+
+    #[test]
+    fn royalty_with_fee_within_price() {
+        assert!(validate_royalty(MAX_ROYALTY_PERCENT, 50).is_ok());
+        assert!(validate_royalty(MAX_ROYALTY_PERCENT + 1, 0).is_err());
+        assert!(validate_royalty(MAX_ROYALTY_PERCENT, 51).is_err());
+        assert!(validate_royalty(0, 255).is_err());
+    }
+
+    #[test]
+    fn dutch_price_decreases_linearly() {
+        let auction = dutch_auction(100, 20, 1000, 2000);
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(1000)),
+            Amount::from_tokens(100)
+        );
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(1250)),
+            Amount::from_tokens(80)
+        );
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(1500)),
+            Amount::from_tokens(60)
+        );
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(2000)),
+            Amount::from_tokens(20)
+        );
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(3000)),
+            Amount::from_tokens(20)
+        );
+    }
+
+    #[test]
+    fn dutch_price_before_creation_and_without_duration() {
+        let auction = dutch_auction(100, 20, 1000, 2000);
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(0)),
+            Amount::from_tokens(100)
+        );
+        let auction = dutch_auction(100, 20, 1000, 1000);
+        assert_eq!(
+            dutch_price(&auction, Timestamp::from(1000)),
+            Amount::from_tokens(20)
+        );
+    }
+
+    #[test]
+    fn plan_pays_fee_and_royalty_with_tokens() {
+        let price = Amount::from_tokens(100);
+        let plan = plan_payment(
+            price,
+            Amount::from_tokens(5),
+            owner(2),
+            Amount::from_tokens(10),
+            Amount::from_tokens(300),
+            Amount::from_tokens(30),
+            30,
+        )
+        .unwrap();
+        assert_eq!(plan.seller_tokens, Amount::from_tokens(55));
+        assert_eq!(plan.tokens, Amount::from_tokens(70));
+        assert_eq!(plan.credits, Amount::from_tokens(300));
+        assert_eq!(plan.publisher, owner(2));
+    }
+
+    #[test]
+    fn plan_caps_credits() {
+        let result = plan_payment(
+            Amount::from_tokens(100),
+            Amount::ZERO,
+            owner(2),
+            Amount::ZERO,
+            Amount::from_tokens(310),
+            Amount::from_tokens(31),
+            30,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn plan_without_credits() {
+        let plan = plan_payment(
+            Amount::from_tokens(100),
+            Amount::from_tokens(5),
+            owner(2),
+            Amount::ZERO,
+            Amount::ZERO,
+            Amount::ZERO,
+            30,
+        )
+        .unwrap();
+        assert_eq!(plan.seller_tokens, Amount::from_tokens(95));
+        assert_eq!(plan.tokens, plan.price);
+    }
+
+    #[test]
+    fn plan_rejects_discount_beyond_seller_share() {
+        let result = plan_payment(
+            Amount::from_tokens(100),
+            Amount::from_tokens(40),
+            owner(2),
+            Amount::from_tokens(40),
+            Amount::from_tokens(300),
+            Amount::from_tokens(30),
+            30,
+        );
+        assert!(result.is_err());
+    }
+
+    fn listing(collection_id: u64, token_id: u16, price: Option<u128>, minted_at: u64) -> Listing {
+        Listing {
+            collection_id,
+            collection_name: String::new(),
+            base_uri: String::new(),
+            uri: String::new(),
+            token_id,
+            name: String::new(),
+            owner: owner(1),
+            price: price.map(Amount::from_tokens),
+            on_sale: true,
+            minted_at: Timestamp::from(minted_at),
+            metadata: NFTMetadata::default(),
+        }
+    }
+
+    fn sorted(order_by: Option<ListingOrder>, listings: &[Listing]) -> Vec<(u64, u16)> {
+        let mut keys = listings
+            .iter()
+            .map(|listing| listing_key(order_by, listing))
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.into_iter()
+            .map(|(_, collection_id, token_id)| (collection_id, token_id))
+            .collect()
+    }
+
+    #[test]
+    fn listing_keys_follow_order() {
+        let listings = vec![
+            listing(1000, 1001, Some(5), 300),
+            listing(1000, 1000, None, 100),
+            listing(1001, 1000, Some(2), 200),
+            listing(999, 1000, Some(5), 400),
+        ];
+        assert_eq!(
+            sorted(Some(ListingOrder::PriceAsc), &listings),
+            vec![(1000, 1000), (1001, 1000), (999, 1000), (1000, 1001)]
+        );
+        assert_eq!(
+            sorted(Some(ListingOrder::PriceDesc), &listings),
+            vec![(999, 1000), (1000, 1001), (1001, 1000), (1000, 1000)]
+        );
+        assert_eq!(
+            sorted(Some(ListingOrder::Newest), &listings),
+            vec![(999, 1000), (1000, 1001), (1001, 1000), (1000, 1000)]
+        );
+        assert_eq!(
+            sorted(Some(ListingOrder::Oldest), &listings),
+            vec![(1000, 1000), (1001, 1000), (1000, 1001), (999, 1000)]
+        );
+        assert_eq!(
+            sorted(None, &listings),
+            vec![(999, 1000), (1000, 1000), (1000, 1001), (1001, 1000)]
+        );
+    }
+
+    #[test]
+    fn listing_cursor_resumes_after_sold_listing() {
+        let keyed = |listings: Vec<Listing>| {
+            listings
+                .into_iter()
+                .map(|listing| (listing_key(Some(ListingOrder::PriceAsc), &listing), listing))
+                .collect::<Vec<_>>()
+        };
+        let listings = vec![
+            listing(1000, 1000, Some(1), 0),
+            listing(1000, 1001, Some(2), 0),
+            listing(1000, 1002, Some(3), 0),
+        ];
+        let (_, cursor) = paginate(keyed(listings.clone()), None, Some(2)).unwrap();
+        // Second listing is taken off sale before the next page
+        let remaining = vec![listings[0].clone(), listings[2].clone()];
+        let (page, cursor) = paginate(keyed(remaining), cursor, Some(2)).unwrap();
+        assert_eq!(page, vec![listings[2].clone()]);
+        assert_eq!(cursor, None);
+    }
+
+    fn metadata(traits: &[(&str, TraitValueType, &str)]) -> NFTMetadata {
+        NFTMetadata {
+            traits: traits
+                .iter()
+                .map(|(trait_type, value_type, value)| NFTTrait {
+                    trait_type: trait_type.to_string(),
+                    value_type: *value_type,
+                    value: value.to_string(),
+                })
+                .collect(),
+            ..NFTMetadata::default()
+        }
+    }
+
+    #[test]
+    fn metadata_values_match_types() {
+        assert!(validate_metadata(&metadata(&[
+            ("color", TraitValueType::String, "red"),
+            ("power", TraitValueType::Number, "-1.5"),
+            ("rare", TraitValueType::Boolean, "true"),
+            ("born", TraitValueType::Date, "1700000000000000"),
+        ]))
+        .is_ok());
+        assert!(validate_metadata(&metadata(&[("color", TraitValueType::String, "")])).is_err());
+        assert!(validate_metadata(&metadata(&[("power", TraitValueType::Number, "x")])).is_err());
+        assert!(validate_metadata(&metadata(&[("rare", TraitValueType::Boolean, "1")])).is_err());
+        assert!(validate_metadata(&metadata(&[("born", TraitValueType::Date, "-1")])).is_err());
+    }
+
+    #[test]
+    fn metadata_trait_types_unique_and_named() {
+        assert!(validate_metadata(&metadata(&[
+            ("color", TraitValueType::String, "red"),
+            ("color", TraitValueType::String, "blue"),
+        ]))
+        .is_err());
+        assert!(validate_metadata(&metadata(&[("", TraitValueType::String, "red")])).is_err());
+        assert!(validate_metadata(&NFTMetadata::default()).is_ok());
+    }
+}
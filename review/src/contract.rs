@@ -4,13 +4,14 @@ mod state;
 
 use std::collections::HashMap;
 
-use self::state::Review;
+use self::state::{activity_object_id, reviewer_object_id, Review};
 use credit::CreditAbi;
 use feed::FeedAbi;
 use foundation::FoundationAbi;
 use linera_sdk::{
     base::{
-        Amount, ApplicationId, ChainId, ChannelName, Destination, MessageId, Owner, WithContractAbi,
+        Amount, ApplicationId, BlockHeight, ChainId, ChannelName, Destination, MessageId, Owner,
+        Timestamp, WithContractAbi,
     },
     views::{RootView, View},
     Contract, ContractRuntime,
@@ -18,10 +19,31 @@ use linera_sdk::{
 // use linera_views::views::ViewError;
 use market::MarketAbi;
 use review::{
-    Asset, Content, InstantiationArgument, Message, Operation, ReviewError, ReviewParameters,
-    ReviewResponse, Reviewer,
+    Asset, BatchReviewItem, BatchReviewResult, Content, InstantiationArgument, Message,
+    Notification, NotificationKind, ObjectType, Operation, Reassignment, ReviewError,
+    ReviewParameters, ReviewResponse, ReviewStatus, Reviewer, RubricScore,
 };
 
+/// Notification of a vote, the vote which decides the application notifies the outcome
+fn vote_notification(approved: bool, decided: bool) -> NotificationKind {
+    match (approved, decided) {
+        (true, true) => NotificationKind::Approved,
+        (true, false) => NotificationKind::ApproveVoted,
+        (false, true) => NotificationKind::Rejected,
+        (false, false) => NotificationKind::RejectVoted,
+    }
+}
+
+/// Vote of a reviewer on an application
+struct Vote {
+    reviewer: Owner,
+    object_type: ObjectType,
+    object_id: String,
+    approved: bool,
+    reason: Option<String>,
+    scores: Vec<RubricScore>,
+}
+
 pub struct ReviewContract {
     state: Review,
     runtime: ContractRuntime<Self>,
@@ -67,11 +89,19 @@ impl Contract for ReviewContract {
             Operation::UpdateReviewerResume { resume } => self
                 .on_op_upeate_reviewer_resume(resume)
                 .expect("Failed OP: update reviewer resume"),
-            Operation::ApproveReviewer { candidate, reason } => self
-                .on_op_approve_reviewer(candidate, reason)
+            Operation::ApproveReviewer {
+                candidate,
+                reason,
+                scores,
+            } => self
+                .on_op_approve_reviewer(candidate, reason, scores)
                 .expect("Failed OP: approve reviewer"),
-            Operation::RejectReviewer { candidate, reason } => self
-                .on_op_reject_reviewer(candidate, reason)
+            Operation::RejectReviewer {
+                candidate,
+                reason,
+                scores,
+            } => self
+                .on_op_reject_reviewer(candidate, reason, scores)
                 .expect("Failed OP: reject reviewer"),
             Operation::SubmitContent {
                 cid,
@@ -79,21 +109,24 @@ impl Contract for ReviewContract {
                 content,
                 cover,
                 abbreviation,
+                publish_at,
             } => self
-                .on_op_submit_content(cid, title, content, cover, abbreviation)
+                .on_op_submit_content(cid, title, content, cover, abbreviation, publish_at)
                 .expect("Failed OP: submit content"),
             Operation::ApproveContent {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             } => self
-                .on_op_approve_content(content_cid, reason_cid, reason)
+                .on_op_approve_content(content_cid, reason_cid, reason, scores)
                 .expect("Failed OP: approve content"),
             Operation::RejectContent {
                 content_cid,
                 reason,
+                scores,
             } => self
-                .on_op_reject_content(content_cid, reason)
+                .on_op_reject_content(content_cid, reason, scores)
                 .expect("Failed OP: reject content"),
             Operation::SubmitComment {
                 cid,
@@ -102,11 +135,19 @@ impl Contract for ReviewContract {
             } => self
                 .on_op_submit_comment(cid, comment_cid, comment)
                 .expect("Failed OP: submit comment"),
-            Operation::ApproveAsset { cid, reason } => self
-                .on_op_approve_asset(cid, reason)
+            Operation::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_op_approve_asset(cid, reason, scores)
                 .expect("Failed OP: approve asset"),
-            Operation::RejectAsset { cid, reason } => self
-                .on_op_reject_asset(cid, reason)
+            Operation::RejectAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_op_reject_asset(cid, reason, scores)
                 .expect("Failed OP: reject asset"),
             Operation::SubmitAsset {
                 cid,
@@ -114,8 +155,9 @@ impl Contract for ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
             } => self
-                .on_op_submit_asset(cid, base_uri, uris, price, name)
+                .on_op_submit_asset(cid, base_uri, uris, price, name, royalty_percent)
                 .expect("Failed OP: submit asset"),
             Operation::RequestSubscribe => self
                 .on_op_request_subscribe()
@@ -123,26 +165,87 @@ impl Contract for ReviewContract {
             Operation::ApproveActivity {
                 activity_id,
                 reason,
+                scores,
             } => self
-                .on_op_approve_activity(activity_id, reason)
+                .on_op_approve_activity(activity_id, reason, scores)
                 .expect("Failed OP: approve activity"),
             Operation::RejectActivity {
                 activity_id,
                 reason,
+                scores,
             } => self
-                .on_op_reject_activity(activity_id, reason)
+                .on_op_reject_activity(activity_id, reason, scores)
                 .expect("Failed OP: reject activity"),
             Operation::SubmitActivity {
                 activity_id,
                 activity_host,
+                host_chain_id,
                 budget_amount,
+                sponsors,
             } => self
-                .on_op_submit_activity(activity_id, activity_host, budget_amount)
+                .on_op_submit_activity(
+                    activity_id,
+                    activity_host,
+                    host_chain_id,
+                    budget_amount,
+                    sponsors,
+                )
                 .expect("Failed OP: submit activity"),
             Operation::ActivityApproved { activity_id } => self
                 .on_op_activity_approved(activity_id)
                 .await
                 .expect("Failed OP: activity approved"),
+            Operation::ExpireApplications => self
+                .on_op_expire_applications()
+                .expect("Failed OP: expire applications"),
+            Operation::SubmitDraft { cid, publish_at } => self
+                .on_op_submit_draft(cid, publish_at)
+                .await
+                .expect("Failed OP: submit draft"),
+            Operation::MarkRead { ids } => self
+                .on_op_mark_read(ids)
+                .await
+                .expect("Failed OP: mark read"),
+            Operation::Resign => self.on_op_resign().expect("Failed OP: resign"),
+            Operation::DeclareConflict { owner } => self
+                .on_op_declare_conflict(owner)
+                .expect("Failed OP: declare conflict"),
+            Operation::ChangeVote {
+                object_type,
+                object_id,
+                approved,
+                reason,
+                scores,
+            } => self
+                .on_op_change_vote(object_type, object_id, approved, reason, scores)
+                .expect("Failed OP: change vote"),
+            Operation::WithdrawVote {
+                object_type,
+                object_id,
+            } => self
+                .on_op_withdraw_vote(object_type, object_id)
+                .expect("Failed OP: withdraw vote"),
+            Operation::Appeal {
+                object_type,
+                id,
+                argument,
+            } => self
+                .on_op_appeal(object_type, id, argument)
+                .expect("Failed OP: appeal"),
+            Operation::ReassignReviewers => self
+                .on_op_reassign_reviewers()
+                .expect("Failed OP: reassign reviewers"),
+            Operation::BatchReview { items } => self
+                .on_op_batch_review(items)
+                .await
+                .expect("Failed OP: batch review"),
+            Operation::ProposeRemoval {
+                reviewer,
+                reason,
+                suspend_ms,
+            } => self
+                .on_op_propose_removal(reviewer, reason, suspend_ms)
+                .expect("Failed OP: propose removal"),
         }
     }
 
@@ -164,12 +267,20 @@ impl Contract for ReviewContract {
                 .on_msg_upeate_reviewer_resume(resume)
                 .await
                 .expect("Failed MSG: update reviewer resume"),
-            Message::ApproveReviewer { candidate, reason } => self
-                .on_msg_approve_reviewer(candidate, reason)
+            Message::ApproveReviewer {
+                candidate,
+                reason,
+                scores,
+            } => self
+                .on_msg_approve_reviewer(candidate, reason, scores)
                 .await
                 .expect("Failed MSG: approve reviewer"),
-            Message::RejectReviewer { candidate, reason } => self
-                .on_msg_reject_reviewer(candidate, reason)
+            Message::RejectReviewer {
+                candidate,
+                reason,
+                scores,
+            } => self
+                .on_msg_reject_reviewer(candidate, reason, scores)
                 .await
                 .expect("Failed MSG: reject reviewer"),
             Message::SubmitContent {
@@ -178,23 +289,26 @@ impl Contract for ReviewContract {
                 content,
                 cover,
                 abbreviation,
+                publish_at,
             } => self
-                .on_msg_submit_content(cid, title, content, cover, abbreviation)
+                .on_msg_submit_content(cid, title, content, cover, abbreviation, publish_at)
                 .await
                 .expect("Failed MSG: submit content"),
             Message::ApproveContent {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             } => self
-                .on_msg_approve_content(content_cid, reason_cid, reason)
+                .on_msg_approve_content(content_cid, reason_cid, reason, scores)
                 .await
                 .expect("Failed MSG: approve content"),
             Message::RejectContent {
                 content_cid,
                 reason,
+                scores,
             } => self
-                .on_msg_reject_content(content_cid, reason)
+                .on_msg_reject_content(content_cid, reason, scores)
                 .await
                 .expect("Failed MSG: reject content"),
             Message::SubmitComment {
@@ -205,12 +319,20 @@ impl Contract for ReviewContract {
                 .on_msg_submit_comment(cid, comment_cid, comment)
                 .await
                 .expect("Failed MSG: submit comment"),
-            Message::ApproveAsset { cid, reason } => self
-                .on_msg_approve_asset(cid, reason)
+            Message::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_msg_approve_asset(cid, reason, scores)
                 .await
                 .expect("Failed MSG: approve asset"),
-            Message::RejectAsset { cid, reason } => self
-                .on_msg_reject_asset(cid, reason)
+            Message::RejectAsset {
+                cid,
+                reason,
+                scores,
+            } => self
+                .on_msg_reject_asset(cid, reason, scores)
                 .await
                 .expect("Failed MSG: reject asset"),
             Message::SubmitAsset {
@@ -219,8 +341,9 @@ impl Contract for ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
             } => self
-                .on_msg_submit_asset(cid, base_uri, uris, price, name)
+                .on_msg_submit_asset(cid, base_uri, uris, price, name, royalty_percent)
                 .await
                 .expect("Failed MSG: submit asset"),
             Message::RequestSubscribe => self
@@ -234,25 +357,103 @@ impl Contract for ReviewContract {
             Message::SubmitActivity {
                 activity_id,
                 activity_host,
+                host_chain_id,
                 budget_amount,
+                sponsors,
             } => self
-                .on_msg_submit_activity(activity_id, activity_host, budget_amount)
+                .on_msg_submit_activity(
+                    activity_id,
+                    activity_host,
+                    host_chain_id,
+                    budget_amount,
+                    sponsors,
+                )
                 .await
                 .expect("Failed MSG: submit activity"),
             Message::ApproveActivity {
                 activity_id,
                 reason,
+                scores,
             } => self
-                .on_msg_approve_activity(activity_id, reason)
+                .on_msg_approve_activity(activity_id, reason, scores)
                 .await
                 .expect("Failed MSG: approve activity"),
             Message::RejectActivity {
                 activity_id,
                 reason,
+                scores,
             } => self
-                .on_msg_reject_activity(activity_id, reason)
+                .on_msg_reject_activity(activity_id, reason, scores)
                 .await
                 .expect("Failed MSG: reject activity"),
+            Message::ExpireApplications => self
+                .on_msg_expire_applications()
+                .await
+                .expect("Failed MSG: expire applications"),
+            Message::ApplicationsExpired {
+                applications,
+                removal_proposals,
+            } => self
+                .on_msg_applications_expired(applications, removal_proposals)
+                .await
+                .expect("Failed MSG: applications expired"),
+            Message::Notify {
+                owner,
+                notification,
+            } => self
+                .on_msg_notify(owner, notification)
+                .await
+                .expect("Failed MSG: notify"),
+            Message::Resign => self.on_msg_resign().await.expect("Failed MSG: resign"),
+            Message::DeclareConflict { owner } => self
+                .on_msg_declare_conflict(owner)
+                .await
+                .expect("Failed MSG: declare conflict"),
+            Message::ChangeVote {
+                object_type,
+                object_id,
+                approved,
+                reason,
+                scores,
+            } => self
+                .on_msg_change_vote(object_type, object_id, approved, reason, scores)
+                .await
+                .expect("Failed MSG: change vote"),
+            Message::WithdrawVote {
+                object_type,
+                object_id,
+            } => self
+                .on_msg_withdraw_vote(object_type, object_id)
+                .await
+                .expect("Failed MSG: withdraw vote"),
+            Message::Appeal {
+                object_type,
+                id,
+                argument,
+            } => self
+                .on_msg_appeal(object_type, id, argument)
+                .await
+                .expect("Failed MSG: appeal"),
+            Message::ReassignReviewers => self
+                .on_msg_reassign_reviewers()
+                .await
+                .expect("Failed MSG: reassign reviewers"),
+            Message::ReviewersReassigned { reassignments } => self
+                .on_msg_reviewers_reassigned(reassignments)
+                .await
+                .expect("Failed MSG: reviewers reassigned"),
+            Message::BatchReview { items } => self
+                .on_msg_batch_review(items)
+                .await
+                .expect("Failed MSG: batch review"),
+            Message::ProposeRemoval {
+                reviewer,
+                reason,
+                suspend_ms,
+            } => self
+                .on_msg_propose_removal(reviewer, reason, suspend_ms)
+                .await
+                .expect("Failed MSG: propose removal"),
         }
     }
 
@@ -297,22 +498,16 @@ impl ReviewContract {
         Ok(())
     }
 
-    async fn publish_content(
-        &mut self,
-        cid: String,
-        title: String,
-        content: String,
-        author: Owner,
-        cover: String,
-        abbreviation: String,
-    ) -> Result<(), ReviewError> {
+    async fn publish_content(&mut self, content: Content) -> Result<(), ReviewError> {
         let call = feed::Operation::Publish {
-            cid: cid.clone(),
-            title,
-            content,
-            author,
-            cover,
-            abbreviation,
+            cid: content.cid,
+            title: content.title,
+            content: content.content,
+            author: content.author,
+            cover: content.cover,
+            abbreviation: content.abbreviation,
+            publish_at: content.publish_at,
+            review_score: content.average_score,
         };
         let feed_app_id = self.feed_app_id();
         self.runtime.call_application(true, feed_app_id, &call);
@@ -353,26 +548,30 @@ impl ReviewContract {
         Ok(())
     }
 
-    async fn create_collection(
-        &mut self,
-        base_uri: String,
-        uris: Vec<String>,
-        price: Option<Amount>,
-        name: String,
-        publisher: Owner,
-    ) -> Result<(), ReviewError> {
+    async fn create_collection(&mut self, asset: Asset) -> Result<(), ReviewError> {
         let call = market::Operation::CreateCollection {
-            base_uri: base_uri.clone(),
-            price,
-            name,
-            uris,
-            publisher,
+            base_uri: asset.base_uri,
+            price: asset.price,
+            name: asset.name,
+            uris: asset.uris,
+            publisher: asset.author,
+            review_score: asset.average_score,
+            royalty_percent: asset.royalty_percent,
         };
         let market_app_id = self.market_app_id();
         self.runtime.call_application(true, market_app_id, &call);
         Ok(())
     }
 
+    async fn take_draft(&mut self, cid: String) -> Result<feed::Draft, ReviewError> {
+        let call = feed::Operation::SubmitDraft { cid };
+        let feed_app_id = self.feed_app_id();
+        match self.runtime.call_application(true, feed_app_id, &call) {
+            feed::FeedResponse::Draft(draft) => Ok(draft),
+            _ => Err(ReviewError::InvalidDraft),
+        }
+    }
+
     async fn lock_activity_funds(
         &mut self,
         activity_id: u64,
@@ -388,6 +587,162 @@ impl ReviewContract {
         Ok(())
     }
 
+    async fn notify(
+        &mut self,
+        owner: Owner,
+        object_type: ObjectType,
+        object_id: String,
+        kind: NotificationKind,
+        reviewer: Option<Owner>,
+        reason: Option<String>,
+    ) -> Result<(), ReviewError> {
+        // Only creation chain knows user chains, and each transition must be notified once
+        let creation_chain_id = self.runtime.application_id().creation.chain_id;
+        if self.runtime.chain_id() != creation_chain_id {
+            return Ok(());
+        }
+        let chain_id = self
+            .state
+            .user_chain(owner)
+            .await?
+            .unwrap_or(creation_chain_id);
+        self.runtime
+            .prepare_message(Message::Notify {
+                owner,
+                notification: Notification {
+                    id: 0,
+                    object_type,
+                    object_id,
+                    kind,
+                    reviewer,
+                    reason,
+                    read: false,
+                    created_at: self.runtime.system_time(),
+                },
+            })
+            .with_authentication()
+            .send_to(chain_id);
+        Ok(())
+    }
+
+    async fn notify_assignees(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<(), ReviewError> {
+        for reviewer in self.state.assignees(object_type, object_id.clone()).await? {
+            self.notify(
+                reviewer,
+                object_type,
+                object_id.clone(),
+                NotificationKind::Assigned,
+                None,
+                None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn content_author(&self, cid: String) -> Result<Owner, ReviewError> {
+        match self.state.content_applications.get(&cid).await? {
+            Some(content) => Ok(content.author),
+            _ => Err(ReviewError::InvalidContent),
+        }
+    }
+
+    async fn asset_author(&self, cid: String) -> Result<Owner, ReviewError> {
+        match self.state.asset_applications.get(&cid).await? {
+            Some(asset) => Ok(asset.author),
+            _ => Err(ReviewError::InvalidContent),
+        }
+    }
+
+    async fn activity_host(&self, activity_id: u64) -> Result<Owner, ReviewError> {
+        match self.state.activity_applications.get(&activity_id).await? {
+            Some(activity) => Ok(activity.activity_host),
+            _ => Err(ReviewError::InvalidActivity),
+        }
+    }
+
+    // Creation chain height when the submission is accepted, the author could not predict it.
+    // Subscribers read it from the broadcast message, which is sent in that block.
+    fn submission_height(&mut self) -> Result<BlockHeight, ReviewError> {
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            return Ok(self.runtime.block_height());
+        }
+        Ok(self.require_message_id()?.height)
+    }
+
+    // User chain is the sender of the submission, only known on creation chain
+    async fn record_user_chain(&mut self, owner: Owner) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let chain_id = self.require_message_id()?.chain_id;
+        self.state.record_user_chain(owner, chain_id).await
+    }
+
+    // Review deadline is decided by creation chain time only, an overdue application is expired
+    // by ExpireApplications and the vote is rejected until then
+    async fn validate_review_time(
+        &mut self,
+        object_type: ObjectType,
+        object_id: &str,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        if self
+            .state
+            .application_overdue(object_type, object_id, self.runtime.system_time())
+            .await?
+        {
+            return Err(ReviewError::ReviewExpired);
+        }
+        Ok(())
+    }
+
+    /// Batch items still valid to vote now, duplicates after the first are dropped
+    async fn valid_batch_items(
+        &mut self,
+        reviewer: Owner,
+        items: Vec<BatchReviewItem>,
+    ) -> Result<Vec<BatchReviewItem>, ReviewError> {
+        let now = self.runtime.system_time();
+        let mut valid_items: Vec<BatchReviewItem> = Vec::new();
+        for item in items {
+            if valid_items.iter().any(|valid| {
+                valid.object_type == item.object_type && valid.object_id == item.object_id
+            }) {
+                continue;
+            }
+            if self
+                .state
+                .validate_vote(
+                    reviewer,
+                    item.object_type,
+                    item.object_id.clone(),
+                    &item.scores,
+                    now,
+                )
+                .await
+                .is_err()
+            {
+                continue;
+            }
+            if self
+                .validate_review_time(item.object_type, &item.object_id)
+                .await
+                .is_err()
+            {
+                continue;
+            }
+            valid_items.push(item);
+        }
+        Ok(valid_items)
+    }
+
     async fn _instantiate(&mut self, argument: InstantiationArgument) -> Result<(), ReviewError> {
         self.state.instantiate_review(argument).await?;
         Ok(())
@@ -400,9 +755,25 @@ impl ReviewContract {
         resume: String,
     ) -> Result<(), ReviewError> {
         self.state
-            .apply_reviewer(chain_id, candidate, resume, self.runtime.system_time())
+            .apply_reviewer(
+                chain_id,
+                candidate,
+                resume,
+                self.submission_height()?,
+                self.runtime.system_time(),
+            )
             .await?;
-        Ok(())
+        self.notify_assignees(ObjectType::Reviewer, candidate.to_string())
+            .await?;
+        self.notify(
+            candidate,
+            ObjectType::Reviewer,
+            candidate.to_string(),
+            NotificationKind::Submitted,
+            None,
+            None,
+        )
+        .await
     }
 
     async fn _update_reviewer_resume(
@@ -419,31 +790,43 @@ impl ReviewContract {
         reviewer: Owner,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Reviewer, &candidate.to_string())
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Reviewer, candidate.to_string(), reviewer)
+            .await?;
         let _reviewer = self
             .state
             .approve_reviewer(
                 reviewer,
                 candidate,
-                reason.unwrap_or_default(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        self.notify(
+            candidate,
+            ObjectType::Reviewer,
+            candidate.to_string(),
+            vote_notification(true, _reviewer.is_some()),
+            Some(reviewer),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        match _reviewer {
-            Some(_reviewer) => {
-                // TODO: notify candidate is reviewer
-            }
-            _ => {
-                // TODO: notify candidate is approved
-            }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(100))
+                .await?;
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(100))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
@@ -452,60 +835,65 @@ impl ReviewContract {
         reviewer: Owner,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Reviewer, &candidate.to_string())
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Reviewer, candidate.to_string(), reviewer)
+            .await?;
         let _reviewer = self
             .state
             .reject_reviewer(
                 reviewer,
                 candidate,
-                reason.unwrap_or_default(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        self.notify(
+            candidate,
+            ObjectType::Reviewer,
+            candidate.to_string(),
+            vote_notification(false, _reviewer.is_some()),
+            Some(reviewer),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        match _reviewer {
-            Some(_reviewer) => {
-                // TODO: notify candidate is reviewer
-            }
-            _ => {
-                // TODO: notify candidate is approved
-            }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(100))
+                .await?;
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(100))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
     async fn _submit_content(
         &mut self,
-        cid: String,
-        title: String,
-        content: String,
-        author: Owner,
-        cover: String,
-        abbreviation: String,
+        content: Content,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
-        self.state
-            .submit_content(Content {
-                // TODO: notify author
-                cid,
-                comment_to_cid: None,
-                title,
-                content,
-                author,
-                cover,
-                abbreviation,
-                reviewers: HashMap::default(),
-                approved: 0,
-                rejected: 0,
-                created_at: self.runtime.system_time(),
-            })
+        let author = content.author;
+        let cid = content.cid.clone();
+        self.state.submit_content(content).await?;
+        self.notify_assignees(ObjectType::Content, cid.clone())
             .await?;
+        self.notify(
+            author,
+            ObjectType::Content,
+            cid,
+            NotificationKind::Submitted,
+            None,
+            None,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
@@ -521,9 +909,18 @@ impl ReviewContract {
         author: Owner,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        if !self.state.comment_needs_review(author).await? {
+            if !creation_chain {
+                return Ok(());
+            }
+            self.comment_content(comment_to_cid, cid, comment, author)
+                .await?;
+            self.reward_credits(author, Amount::from_tokens(10)).await?;
+            return Ok(());
+        }
         self.state
             .submit_content(Content {
-                cid,
+                cid: cid.clone(),
                 comment_to_cid: Some(comment_to_cid),
                 title: String::default(),
                 content: comment,
@@ -534,8 +931,28 @@ impl ReviewContract {
                 approved: 0,
                 rejected: 0,
                 created_at: self.runtime.system_time(),
+                publish_at: None,
+                status: ReviewStatus::Pending,
+                excluded_reviewers: Vec::new(),
+                vote_history: Vec::new(),
+                appeal: None,
+                assignees: Vec::new(),
+                rubric_scores: Vec::new(),
+                average_score: None,
+                submitted_height: self.submission_height()?,
             })
             .await?;
+        self.notify_assignees(ObjectType::Content, cid.clone())
+            .await?;
+        self.notify(
+            author,
+            ObjectType::Content,
+            cid,
+            NotificationKind::Submitted,
+            None,
+            None,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
@@ -549,17 +966,35 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Content, &content_cid)
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Content, content_cid.clone(), reviewer)
+            .await?;
         let content = self
             .state
             .approve_content(
                 reviewer,
                 content_cid.clone(),
                 reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        let author = self.content_author(content_cid.clone()).await?;
+        self.notify(
+            author,
+            ObjectType::Content,
+            content_cid.clone(),
+            vote_notification(true, content.is_some()),
+            Some(reviewer),
+            reason.clone(),
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
@@ -575,17 +1010,7 @@ impl ReviewContract {
                         )
                         .await?;
                     }
-                    _ => {
-                        self.publish_content(
-                            content.cid,
-                            content.title,
-                            content.content,
-                            content.author,
-                            content.cover,
-                            content.abbreviation,
-                        )
-                        .await?
-                    }
+                    _ => self.publish_content(content).await?,
                 }
                 match reason_cid {
                     Some(cid) => {
@@ -594,15 +1019,15 @@ impl ReviewContract {
                     }
                     _ => {}
                 }
-                // TODO: notify author content is published
-            }
-            _ => {
-                // TODO: notify author content is approved
             }
+            _ => {}
+        }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(50))
+                .await?;
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(50))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
@@ -611,31 +1036,44 @@ impl ReviewContract {
         reviewer: Owner,
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Content, &content_cid)
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Content, content_cid.clone(), reviewer)
+            .await?;
         let content = self
             .state
             .reject_content(
                 reviewer,
-                content_cid,
-                reason.unwrap_or_default(),
+                content_cid.clone(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        let author = self.content_author(content_cid.clone()).await?;
+        self.notify(
+            author,
+            ObjectType::Content,
+            content_cid,
+            vote_notification(false, content.is_some()),
+            Some(reviewer),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        match content {
-            Some(_content) => {
-                // TODO: notify author content is rejected
-            }
-            _ => {
-                // TODO: notify author content is rejected
-            }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(50))
+                .await?;
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(50))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
@@ -644,39 +1082,46 @@ impl ReviewContract {
         reviewer: Owner,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Asset, &cid).await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Asset, cid.clone(), reviewer)
+            .await?;
         let asset = self
             .state
             .approve_asset(
                 reviewer,
-                cid,
-                reason.unwrap_or_default(),
+                cid.clone(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        let author = self.asset_author(cid.clone()).await?;
+        self.notify(
+            author,
+            ObjectType::Asset,
+            cid,
+            vote_notification(true, asset.is_some()),
+            Some(reviewer),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        match asset {
-            Some(asset) => {
-                self.create_collection(
-                    asset.base_uri,
-                    asset.uris,
-                    asset.price,
-                    asset.name,
-                    asset.author,
-                )
+        if let Some(asset) = asset {
+            self.create_collection(asset).await?;
+        }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(50))
                 .await?;
-                // TODO: notify author is approved
-            }
-            _ => {
-                // TODO: notify author
-            }
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(50))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
@@ -685,58 +1130,61 @@ impl ReviewContract {
         reviewer: Owner,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Asset, &cid).await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Asset, cid.clone(), reviewer)
+            .await?;
         let asset = self
             .state
             .reject_asset(
                 reviewer,
-                cid,
-                reason.unwrap_or_default(),
+                cid.clone(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        let author = self.asset_author(cid.clone()).await?;
+        self.notify(
+            author,
+            ObjectType::Asset,
+            cid,
+            vote_notification(false, asset.is_some()),
+            Some(reviewer),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        match asset {
-            Some(_asset) => {
-                // TODO: notify author is approved
-            }
-            _ => {
-                // TODO: notify author
-            }
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(reviewer, Amount::from_tokens(50))
+                .await?;
+            self.reward_tokens().await?;
         }
-        self.reward_credits(reviewer, Amount::from_tokens(50))
-            .await?;
-        self.reward_tokens().await?;
         Ok(())
     }
 
-    async fn _submit_asset(
-        &mut self,
-        author: Owner,
-        cid: String,
-        base_uri: String,
-        uris: Vec<String>,
-        price: Option<Amount>,
-        name: String,
-    ) -> Result<(), ReviewError> {
-        self.state
-            .submit_asset(Asset {
-                cid,
-                author,
-                base_uri,
-                uris,
-                price,
-                name,
-                reviewers: HashMap::default(),
-                approved: 0,
-                rejected: 0,
-                created_at: self.runtime.system_time(),
-            })
+    async fn _submit_asset(&mut self, asset: Asset) -> Result<(), ReviewError> {
+        let author = asset.author;
+        let cid = asset.cid.clone();
+        self.state.submit_asset(asset).await?;
+        self.notify_assignees(ObjectType::Asset, cid.clone())
             .await?;
-        Ok(())
+        self.notify(
+            author,
+            ObjectType::Asset,
+            cid,
+            NotificationKind::Submitted,
+            None,
+            None,
+        )
+        .await
     }
 
     async fn _submit_activity(
@@ -744,16 +1192,29 @@ impl ReviewContract {
         activity_id: u64,
         activity_host: Owner,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
     ) -> Result<(), ReviewError> {
         self.state
             .submit_activity(
                 activity_id,
                 activity_host,
                 budget_amount,
+                sponsors,
+                self.submission_height()?,
                 self.runtime.system_time(),
             )
             .await?;
-        Ok(())
+        self.notify_assignees(ObjectType::Activity, activity_id.to_string())
+            .await?;
+        self.notify(
+            activity_host,
+            ObjectType::Activity,
+            activity_id.to_string(),
+            NotificationKind::Submitted,
+            None,
+            None,
+        )
+        .await
     }
 
     async fn _approve_activity(
@@ -761,22 +1222,43 @@ impl ReviewContract {
         owner: Owner,
         activity_id: u64,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Activity, &activity_id.to_string())
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Activity, activity_id.to_string(), owner)
+            .await?;
         let activity = self
             .state
             .approve_activity(
                 owner,
                 activity_id,
-                reason.unwrap_or_default(),
+                reason.clone().unwrap_or_default(),
+                scores,
                 self.runtime.system_time(),
             )
             .await?;
+        let host = self.activity_host(activity_id).await?;
+        self.notify(
+            host,
+            ObjectType::Activity,
+            activity_id.to_string(),
+            vote_notification(true, activity.is_some()),
+            Some(owner),
+            reason,
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        self.reward_credits(owner, Amount::from_tokens(50)).await?;
-        self.reward_tokens().await?;
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(owner, Amount::from_tokens(50)).await?;
+            self.reward_tokens().await?;
+        }
         if let Some(activity) = activity {
             self.lock_activity_funds(activity_id, activity.budget_amount)
                 .await?;
@@ -789,17 +1271,159 @@ impl ReviewContract {
         owner: Owner,
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
         creation_chain: bool,
     ) -> Result<(), ReviewError> {
+        self.validate_review_time(ObjectType::Activity, &activity_id.to_string())
+            .await?;
+        let revoted = self
+            .state
+            .revoted(ObjectType::Activity, activity_id.to_string(), owner)
+            .await?;
         let _activity = self
             .state
-            .reject_activity(owner, activity_id, reason, self.runtime.system_time())
+            .reject_activity(
+                owner,
+                activity_id,
+                reason.clone(),
+                scores,
+                self.runtime.system_time(),
+            )
             .await?;
+        let host = self.activity_host(activity_id).await?;
+        self.notify(
+            host,
+            ObjectType::Activity,
+            activity_id.to_string(),
+            vote_notification(false, _activity.is_some()),
+            Some(owner),
+            Some(reason),
+        )
+        .await?;
         if !creation_chain {
             return Ok(());
         }
-        self.reward_credits(owner, Amount::from_tokens(50)).await?;
-        self.reward_tokens().await?;
+        // Changed vote is not rewarded again
+        if !revoted {
+            self.reward_credits(owner, Amount::from_tokens(50)).await?;
+            self.reward_tokens().await?;
+        }
+        Ok(())
+    }
+
+    async fn _withdraw_vote(
+        &mut self,
+        reviewer: Owner,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<Owner, ReviewError> {
+        self.validate_review_time(object_type, &object_id).await?;
+        self.state
+            .withdraw_vote(object_type, object_id, reviewer)
+            .await
+    }
+
+    async fn _change_vote(&mut self, vote: Vote, creation_chain: bool) -> Result<(), ReviewError> {
+        self._withdraw_vote(vote.reviewer, vote.object_type, vote.object_id.clone())
+            .await?;
+        self._vote(vote, creation_chain).await
+    }
+
+    async fn _vote(&mut self, vote: Vote, creation_chain: bool) -> Result<(), ReviewError> {
+        let Vote {
+            reviewer,
+            object_type,
+            object_id,
+            approved,
+            reason,
+            scores,
+        } = vote;
+        match (object_type, approved) {
+            (ObjectType::Content, true) => {
+                self._approve_content(reviewer, object_id, None, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Content, false) => {
+                self._reject_content(reviewer, object_id, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Asset, true) => {
+                self._approve_asset(reviewer, object_id, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Asset, false) => {
+                self._reject_asset(reviewer, object_id, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Reviewer, true) => {
+                let candidate = reviewer_object_id(&object_id)?;
+                self._approve_reviewer(reviewer, candidate, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Reviewer, false) => {
+                let candidate = reviewer_object_id(&object_id)?;
+                self._reject_reviewer(reviewer, candidate, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Activity, true) => {
+                let activity_id = activity_object_id(&object_id)?;
+                self._approve_activity(reviewer, activity_id, reason, scores, creation_chain)
+                    .await
+            }
+            (ObjectType::Activity, false) => {
+                let activity_id = activity_object_id(&object_id)?;
+                self._reject_activity(
+                    reviewer,
+                    activity_id,
+                    reason.unwrap_or_default(),
+                    scores,
+                    creation_chain,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn _propose_removal(
+        &mut self,
+        owner: Owner,
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+    ) -> Result<(), ReviewError> {
+        if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id
+            && self
+                .state
+                .removal_proposal_overdue(reviewer, self.runtime.system_time())
+                .await?
+        {
+            return Err(ReviewError::ReviewExpired);
+        }
+        let proposal = self
+            .state
+            .propose_removal(
+                owner,
+                reviewer,
+                reason,
+                suspend_ms,
+                self.runtime.system_time(),
+            )
+            .await?;
+        if let Some(proposal) = proposal {
+            let kind = match proposal.suspend_ms {
+                Some(_) => NotificationKind::Suspended,
+                None => NotificationKind::Removed,
+            };
+            self.notify(
+                reviewer,
+                ObjectType::Reviewer,
+                reviewer.to_string(),
+                kind,
+                None,
+                Some(proposal.reason),
+            )
+            .await?;
+        }
         Ok(())
     }
 
@@ -840,9 +1464,14 @@ impl ReviewContract {
         &mut self,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::ApproveReviewer { candidate, reason })
+            .prepare_message(Message::ApproveReviewer {
+                candidate,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
@@ -852,9 +1481,14 @@ impl ReviewContract {
         &mut self,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::RejectReviewer { candidate, reason })
+            .prepare_message(Message::RejectReviewer {
+                candidate,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
@@ -867,6 +1501,7 @@ impl ReviewContract {
         content: String,
         cover: String,
         abbreviation: String,
+        publish_at: Option<Timestamp>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::SubmitContent {
@@ -875,6 +1510,7 @@ impl ReviewContract {
                 content,
                 cover,
                 abbreviation,
+                publish_at,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -886,12 +1522,14 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::ApproveContent {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -902,11 +1540,13 @@ impl ReviewContract {
         &mut self,
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::RejectContent {
                 content_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -934,9 +1574,14 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::ApproveAsset { cid, reason })
+            .prepare_message(Message::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
@@ -946,9 +1591,14 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
-            .prepare_message(Message::RejectAsset { cid, reason })
+            .prepare_message(Message::RejectAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
         Ok(ReviewResponse::Ok)
@@ -961,6 +1611,7 @@ impl ReviewContract {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::SubmitAsset {
@@ -969,6 +1620,7 @@ impl ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -987,11 +1639,13 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::ApproveActivity {
                 activity_id,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -1003,11 +1657,13 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::RejectActivity {
                 activity_id,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -1019,13 +1675,17 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         activity_host: Owner,
+        host_chain_id: ChainId,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
     ) -> Result<ReviewResponse, ReviewError> {
         self.runtime
             .prepare_message(Message::SubmitActivity {
                 activity_id,
                 activity_host,
+                host_chain_id,
                 budget_amount,
+                sponsors,
             })
             .with_authentication()
             .send_to(self.runtime.application_id().creation.chain_id);
@@ -1033,6 +1693,174 @@ impl ReviewContract {
         Ok(ReviewResponse::Ok)
     }
 
+    fn on_op_expire_applications(&mut self) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ExpireApplications)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    async fn on_op_submit_draft(
+        &mut self,
+        cid: String,
+        publish_at: Option<Timestamp>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        // Draft lives on the author's chain, so it's taken out here then submitted as content
+        let draft = self.take_draft(cid).await?;
+        self.on_op_submit_content(
+            draft.cid,
+            draft.title,
+            draft.content,
+            draft.cover,
+            draft.abbreviation,
+            publish_at,
+        )
+    }
+
+    async fn on_op_mark_read(&mut self, ids: Vec<u64>) -> Result<ReviewResponse, ReviewError> {
+        // Inbox lives on the user's chain, so marking read never leaves it
+        let owner = self.require_authenticated_signer()?;
+        self.state.mark_read(owner, ids).await?;
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_resign(&mut self) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::Resign)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_declare_conflict(&mut self, owner: Owner) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::DeclareConflict { owner })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_change_vote(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+        approved: bool,
+        reason: Option<String>,
+        scores: Vec<RubricScore>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ChangeVote {
+                object_type,
+                object_id,
+                approved,
+                reason,
+                scores,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_withdraw_vote(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::WithdrawVote {
+                object_type,
+                object_id,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    async fn on_op_batch_review(
+        &mut self,
+        items: Vec<BatchReviewItem>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let now = self.runtime.system_time();
+        let mut results = Vec::new();
+        let mut valid_items: Vec<BatchReviewItem> = Vec::new();
+        for item in items {
+            let validated = if valid_items.iter().any(|valid| {
+                valid.object_type == item.object_type && valid.object_id == item.object_id
+            }) {
+                Err(ReviewError::AlreadyReviewed)
+            } else {
+                self.state
+                    .validate_vote(
+                        reviewer,
+                        item.object_type,
+                        item.object_id.clone(),
+                        &item.scores,
+                        now,
+                    )
+                    .await
+            };
+            results.push(BatchReviewResult {
+                object_type: item.object_type,
+                object_id: item.object_id.clone(),
+                error: validated.as_ref().err().map(|err| err.to_string()),
+            });
+            if validated.is_ok() {
+                valid_items.push(item);
+            }
+        }
+        if !valid_items.is_empty() {
+            self.runtime
+                .prepare_message(Message::BatchReview { items: valid_items })
+                .with_authentication()
+                .send_to(self.runtime.application_id().creation.chain_id);
+        }
+        Ok(ReviewResponse::BatchReview(results))
+    }
+
+    fn on_op_reassign_reviewers(&mut self) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ReassignReviewers)
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_appeal(
+        &mut self,
+        object_type: ObjectType,
+        id: String,
+        argument: String,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::Appeal {
+                object_type,
+                id,
+                argument,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
+    fn on_op_propose_removal(
+        &mut self,
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+    ) -> Result<ReviewResponse, ReviewError> {
+        self.runtime
+            .prepare_message(Message::ProposeRemoval {
+                reviewer,
+                reason,
+                suspend_ms,
+            })
+            .with_authentication()
+            .send_to(self.runtime.application_id().creation.chain_id);
+        Ok(ReviewResponse::Ok)
+    }
+
     async fn on_op_activity_approved(
         &mut self,
         activity_id: u64,
@@ -1073,6 +1901,7 @@ impl ReviewContract {
 
     async fn on_msg_apply_reviewer(&mut self, resume: String) -> Result<(), ReviewError> {
         let candidate = self.require_authenticated_signer()?;
+        self.record_user_chain(candidate).await?;
         let chain_id = self.runtime.chain_id();
         self._apply_reviewer(chain_id, candidate, resume.clone())
             .await?;
@@ -1106,18 +1935,29 @@ impl ReviewContract {
         &mut self,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._approve_reviewer(reviewer, candidate, reason.clone(), creation_chain)
-            .await?;
+        self._approve_reviewer(
+            reviewer,
+            candidate,
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::ApproveReviewer { candidate, reason })
+            .prepare_message(Message::ApproveReviewer {
+                candidate,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -1127,18 +1967,29 @@ impl ReviewContract {
         &mut self,
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._reject_reviewer(reviewer, candidate, reason.clone(), creation_chain)
-            .await?;
+        self._reject_reviewer(
+            reviewer,
+            candidate,
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::RejectReviewer { candidate, reason })
+            .prepare_message(Message::RejectReviewer {
+                candidate,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -1151,20 +2002,35 @@ impl ReviewContract {
         content: String,
         cover: String,
         abbreviation: String,
+        publish_at: Option<Timestamp>,
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
+        self.record_user_chain(author).await?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._submit_content(
-            cid.clone(),
-            title.clone(),
-            content.clone(),
+        let submission = Content {
+            cid: cid.clone(),
+            comment_to_cid: None,
+            title: title.clone(),
+            content: content.clone(),
             author,
-            cover.clone(),
-            abbreviation.clone(),
-            creation_chain,
-        )
-        .await?;
+            cover: cover.clone(),
+            abbreviation: abbreviation.clone(),
+            reviewers: HashMap::default(),
+            approved: 0,
+            rejected: 0,
+            created_at: self.runtime.system_time(),
+            publish_at,
+            status: ReviewStatus::Pending,
+            excluded_reviewers: Vec::new(),
+            vote_history: Vec::new(),
+            appeal: None,
+            assignees: Vec::new(),
+            rubric_scores: Vec::new(),
+            average_score: None,
+            submitted_height: self.submission_height()?,
+        };
+        self._submit_content(submission, creation_chain).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -1176,6 +2042,7 @@ impl ReviewContract {
                 content,
                 cover,
                 abbreviation,
+                publish_at,
             })
             .with_authentication()
             .send_to(dest);
@@ -1187,6 +2054,7 @@ impl ReviewContract {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
@@ -1196,6 +2064,7 @@ impl ReviewContract {
             content_cid.clone(),
             reason_cid.clone(),
             reason.clone(),
+            scores.clone(),
             creation_chain,
         )
         .await?;
@@ -1208,6 +2077,7 @@ impl ReviewContract {
                 content_cid,
                 reason_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1218,6 +2088,7 @@ impl ReviewContract {
         &mut self,
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
@@ -1226,6 +2097,7 @@ impl ReviewContract {
             reviewer,
             content_cid.clone(),
             reason.clone(),
+            scores.clone(),
             creation_chain,
         )
         .await?;
@@ -1237,6 +2109,7 @@ impl ReviewContract {
             .prepare_message(Message::RejectContent {
                 content_cid,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1250,6 +2123,7 @@ impl ReviewContract {
         comment: String,
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
+        self.record_user_chain(author).await?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
         self._submit_comment(
@@ -1279,18 +2153,29 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._approve_asset(reviewer, cid.clone(), reason.clone(), creation_chain)
-            .await?;
+        self._approve_asset(
+            reviewer,
+            cid.clone(),
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::ApproveAsset { cid, reason })
+            .prepare_message(Message::ApproveAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -1300,18 +2185,29 @@ impl ReviewContract {
         &mut self,
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._reject_asset(reviewer, cid.clone(), reason.clone(), creation_chain)
-            .await?;
+        self._reject_asset(
+            reviewer,
+            cid.clone(),
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
-            .prepare_message(Message::RejectAsset { cid, reason })
+            .prepare_message(Message::RejectAsset {
+                cid,
+                reason,
+                scores,
+            })
             .with_authentication()
             .send_to(dest);
         Ok(())
@@ -1324,17 +2220,32 @@ impl ReviewContract {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
     ) -> Result<(), ReviewError> {
         let author = self.require_authenticated_signer()?;
-        self._submit_asset(
+        self.record_user_chain(author).await?;
+        let asset = Asset {
+            cid: cid.clone(),
             author,
-            cid.clone(),
-            base_uri.clone(),
-            uris.clone(),
-            price.clone(),
-            name.clone(),
-        )
-        .await?;
+            base_uri: base_uri.clone(),
+            uris: uris.clone(),
+            price,
+            name: name.clone(),
+            royalty_percent,
+            reviewers: HashMap::default(),
+            approved: 0,
+            rejected: 0,
+            created_at: self.runtime.system_time(),
+            status: ReviewStatus::Pending,
+            excluded_reviewers: Vec::new(),
+            vote_history: Vec::new(),
+            appeal: None,
+            assignees: Vec::new(),
+            rubric_scores: Vec::new(),
+            average_score: None,
+            submitted_height: self.submission_height()?,
+        };
+        self._submit_asset(asset).await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -1346,6 +2257,7 @@ impl ReviewContract {
                 uris,
                 price,
                 name,
+                royalty_percent,
             })
             .with_authentication()
             .send_to(dest);
@@ -1388,12 +2300,19 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._approve_activity(reviewer, activity_id, reason.clone(), creation_chain)
-            .await?;
+        self._approve_activity(
+            reviewer,
+            activity_id,
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -1402,6 +2321,7 @@ impl ReviewContract {
             .prepare_message(Message::ApproveActivity {
                 activity_id,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1412,12 +2332,19 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
     ) -> Result<(), ReviewError> {
         let reviewer = self.require_authenticated_signer()?;
         let creation_chain =
             self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
-        self._reject_activity(reviewer, activity_id, reason.clone(), creation_chain)
-            .await?;
+        self._reject_activity(
+            reviewer,
+            activity_id,
+            reason.clone(),
+            scores.clone(),
+            creation_chain,
+        )
+        .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
@@ -1426,6 +2353,7 @@ impl ReviewContract {
             .prepare_message(Message::RejectActivity {
                 activity_id,
                 reason,
+                scores,
             })
             .with_authentication()
             .send_to(dest);
@@ -1436,22 +2364,354 @@ impl ReviewContract {
         &mut self,
         activity_id: u64,
         activity_host: Owner,
+        host_chain_id: ChainId,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
     ) -> Result<(), ReviewError> {
-        self._submit_activity(activity_id, activity_host, budget_amount)
+        self._submit_activity(activity_id, activity_host, budget_amount, sponsors.clone())
             .await?;
         if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
             return Ok(());
         }
+        // Activity application submits from its own chain, so the host chain is passed along
+        self.state
+            .record_user_chain(activity_host, host_chain_id)
+            .await?;
         let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
         self.runtime
             .prepare_message(Message::SubmitActivity {
                 activity_id,
                 activity_host,
+                host_chain_id,
                 budget_amount,
+                sponsors,
             })
             .with_authentication()
             .send_to(dest);
         Ok(())
     }
+
+    async fn on_msg_expire_applications(&mut self) -> Result<(), ReviewError> {
+        // Deadline is only decided with creation chain time, subscribers follow ApplicationsExpired
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let now = self.runtime.system_time();
+        let applications = self.state.overdue_applications(now).await?;
+        let removal_proposals = self.state.overdue_removal_proposals(now).await?;
+        if applications.is_empty() && removal_proposals.is_empty() {
+            return Ok(());
+        }
+        self.applications_expired(applications.clone(), removal_proposals.clone())
+            .await?;
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ApplicationsExpired {
+                applications,
+                removal_proposals,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn applications_expired(
+        &mut self,
+        applications: Vec<(ObjectType, String)>,
+        removal_proposals: Vec<Owner>,
+    ) -> Result<(), ReviewError> {
+        for reviewer in removal_proposals {
+            self.state.expire_removal_proposal(reviewer).await?;
+        }
+        for (object_type, object_id) in applications {
+            if let Some(author) = self
+                .state
+                .expire_application(object_type, object_id.clone())
+                .await?
+            {
+                self.notify(
+                    author,
+                    object_type,
+                    object_id,
+                    NotificationKind::Expired,
+                    None,
+                    None,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn on_msg_applications_expired(
+        &mut self,
+        applications: Vec<(ObjectType, String)>,
+        removal_proposals: Vec<Owner>,
+    ) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::InvalidMessageId);
+        }
+        self.applications_expired(applications, removal_proposals)
+            .await
+    }
+
+    async fn on_msg_notify(
+        &mut self,
+        owner: Owner,
+        notification: Notification,
+    ) -> Result<(), ReviewError> {
+        // Notification is only trusted when it's sent by creation chain
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::InvalidNotification);
+        }
+        self.state.notify(owner, notification).await
+    }
+
+    async fn on_msg_resign(&mut self) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        self.state.resign_reviewer(reviewer).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Resign)
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_propose_removal(
+        &mut self,
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+    ) -> Result<(), ReviewError> {
+        let owner = self.require_authenticated_signer()?;
+        self._propose_removal(owner, reviewer, reason.clone(), suspend_ms)
+            .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ProposeRemoval {
+                reviewer,
+                reason,
+                suspend_ms,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_declare_conflict(&mut self, owner: Owner) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        self.state.declare_conflict(reviewer, owner).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::DeclareConflict { owner })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_change_vote(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+        approved: bool,
+        reason: Option<String>,
+        scores: Vec<RubricScore>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+        let vote = Vote {
+            reviewer,
+            object_type,
+            object_id: object_id.clone(),
+            approved,
+            reason: reason.clone(),
+            scores: scores.clone(),
+        };
+        self._change_vote(vote, creation_chain).await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ChangeVote {
+                object_type,
+                object_id,
+                approved,
+                reason,
+                scores,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_withdraw_vote(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let owner = self
+            ._withdraw_vote(reviewer, object_type, object_id.clone())
+            .await?;
+        self.notify(
+            owner,
+            object_type,
+            object_id.clone(),
+            NotificationKind::VoteWithdrawn,
+            Some(reviewer),
+            None,
+        )
+        .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::WithdrawVote {
+                object_type,
+                object_id,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_appeal(
+        &mut self,
+        object_type: ObjectType,
+        id: String,
+        argument: String,
+    ) -> Result<(), ReviewError> {
+        let owner = self.require_authenticated_signer()?;
+        self.state
+            .appeal(
+                owner,
+                object_type,
+                id.clone(),
+                argument.clone(),
+                self.runtime.system_time(),
+            )
+            .await?;
+        self.notify_assignees(object_type, id.clone()).await?;
+        self.notify(
+            owner,
+            object_type,
+            id.clone(),
+            NotificationKind::Appealed,
+            None,
+            Some(argument.clone()),
+        )
+        .await?;
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::Appeal {
+                object_type,
+                id,
+                argument,
+            })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_batch_review(
+        &mut self,
+        items: Vec<BatchReviewItem>,
+    ) -> Result<(), ReviewError> {
+        let reviewer = self.require_authenticated_signer()?;
+        let creation_chain =
+            self.runtime.chain_id() != self.runtime.application_id().creation.chain_id;
+        // Creation chain drops votes invalidated since the operation before changing anything,
+        // subscribers only get the applied ones. Any failure applying them fails the message
+        let items = if self.runtime.chain_id() == self.runtime.application_id().creation.chain_id {
+            self.valid_batch_items(reviewer, items).await?
+        } else {
+            items
+        };
+        for item in items.clone() {
+            let vote = Vote {
+                reviewer,
+                object_type: item.object_type,
+                object_id: item.object_id,
+                approved: item.approved,
+                reason: item.reason,
+                scores: item.scores,
+            };
+            self._vote(vote, creation_chain).await?;
+        }
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        if items.is_empty() {
+            return Ok(());
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::BatchReview { items })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_reassign_reviewers(&mut self) -> Result<(), ReviewError> {
+        // Deadline is only decided with creation chain time, subscribers follow
+        // ReviewersReassigned
+        if self.runtime.chain_id() != self.runtime.application_id().creation.chain_id {
+            return Ok(());
+        }
+        let reassignments = self
+            .state
+            .reassign_reviewers(self.runtime.system_time())
+            .await?;
+        if reassignments.is_empty() {
+            return Ok(());
+        }
+        for reassignment in &reassignments {
+            for assignment in &reassignment.assigned {
+                self.notify(
+                    assignment.reviewer,
+                    reassignment.object_type,
+                    reassignment.object_id.clone(),
+                    NotificationKind::Assigned,
+                    None,
+                    None,
+                )
+                .await?;
+            }
+        }
+        let dest = Destination::Subscribers(ChannelName::from(SUBSCRIPTION_CHANNEL.to_vec()));
+        self.runtime
+            .prepare_message(Message::ReviewersReassigned { reassignments })
+            .with_authentication()
+            .send_to(dest);
+        Ok(())
+    }
+
+    async fn on_msg_reviewers_reassigned(
+        &mut self,
+        reassignments: Vec<Reassignment>,
+    ) -> Result<(), ReviewError> {
+        if self.require_message_id()?.chain_id != self.runtime.application_id().creation.chain_id {
+            return Err(ReviewError::InvalidMessageId);
+        }
+        for reassignment in &reassignments {
+            self.state.apply_reassignment(reassignment).await?;
+        }
+        Ok(())
+    }
 }
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, InputObject, OutputType, Request, Response, SimpleObject};
 use linera_sdk::{
     base::{
-        Amount, ApplicationId, ArithmeticError, ChainId, ContractAbi, Owner, ServiceAbi, Timestamp,
+        Amount, ApplicationId, ArithmeticError, BlockHeight, ChainId, ContractAbi, Owner,
+        ServiceAbi, Timestamp,
     },
     graphql::GraphQLMutationRoot,
 };
@@ -27,6 +28,7 @@ pub enum ReviewResponse {
     #[default]
     Ok,
     Approved(bool),
+    BatchReview(Vec<BatchReviewResult>),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -37,6 +39,105 @@ pub struct ReviewParameters {
     pub market_app_id: ApplicationId<market::MarketAbi>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, PartialOrd, Default)]
+pub enum DecisionMode {
+    /// Compare raw vote count with threshold
+    #[default]
+    Count,
+    /// Compare reputation weighted votes with percent of total reviewer weight
+    Weighted,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, PartialOrd, Default)]
+pub enum CommentModeration {
+    /// Comments are queued and reviewed like contents
+    #[default]
+    Review,
+    /// Comments are published to feed without review
+    PassThrough,
+}
+
+#[derive(
+    Debug, Deserialize, Serialize, Copy, Clone, SimpleObject, Eq, PartialEq, PartialOrd, Default,
+)]
+pub struct DecisionPolicy {
+    pub mode: DecisionMode,
+    /// Only used in weighted mode, approved when approval weight exceeds this percent
+    pub approved_percent: u8,
+    /// Only used in weighted mode, rejected when rejection weight exceeds this percent
+    pub rejected_percent: u8,
+}
+
+impl DecisionPolicy {
+    /// Percents are only used in weighted mode, where they must be within 1..=99 since
+    /// weight must exceed the percent and 100 could never be reached
+    pub fn validate(&self) -> Result<(), ReviewError> {
+        if self.mode == DecisionMode::Count {
+            return Ok(());
+        }
+        if !(1..=99).contains(&self.approved_percent) || !(1..=99).contains(&self.rejected_percent)
+        {
+            return Err(ReviewError::InvalidDecisionPolicy);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, Default)]
+pub enum ReviewStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+    /// Not resolved before review deadline
+    Expired,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq, PartialOrd)]
+pub enum ObjectType {
+    Content,
+    Asset,
+    Reviewer,
+    Activity,
+}
+
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Enum, Eq, PartialEq)]
+pub enum NotificationKind {
+    Submitted,
+    /// A reviewer voted to approve, the application is still pending
+    ApproveVoted,
+    /// A reviewer voted to reject, the application is still pending
+    RejectVoted,
+    Approved,
+    Rejected,
+    Expired,
+    /// Reviewer is removed by other reviewers
+    Removed,
+    /// Reviewer is suspended by other reviewers
+    Suspended,
+    /// A reviewer withdrew the vote, the application is still pending
+    VoteWithdrawn,
+    /// Rejected application is reopened by the owner
+    Appealed,
+    /// Reviewer is assigned to review the application
+    Assigned,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Notification {
+    /// Sequence of the notification in the owner's inbox, assigned by the receiving chain and
+    /// kept when older notifications are pruned
+    pub id: u64,
+    pub object_type: ObjectType,
+    /// Content or asset cid, candidate owner or activity id
+    pub object_id: String,
+    pub kind: NotificationKind,
+    pub reviewer: Option<Owner>,
+    pub reason: Option<String>,
+    pub read: bool,
+    pub created_at: Timestamp,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, PartialOrd, Serialize)]
 pub struct InstantiationArgument {
     pub content_approved_threshold: u16,
@@ -47,6 +148,54 @@ pub struct InstantiationArgument {
     pub reviewer_rejected_threshold: u16,
     pub activity_approved_threshold: u16,
     pub activity_rejected_threshold: u16,
+    #[serde(default)]
+    pub content_decision: DecisionPolicy,
+    #[serde(default)]
+    pub asset_decision: DecisionPolicy,
+    #[serde(default)]
+    pub reviewer_decision: DecisionPolicy,
+    #[serde(default)]
+    pub activity_decision: DecisionPolicy,
+    /// Pending application expires after deadline, None means never expire
+    #[serde(default)]
+    pub content_review_deadline_ms: Option<u64>,
+    #[serde(default)]
+    pub asset_review_deadline_ms: Option<u64>,
+    #[serde(default)]
+    pub reviewer_review_deadline_ms: Option<u64>,
+    #[serde(default)]
+    pub activity_review_deadline_ms: Option<u64>,
+    /// Threshold of appealed applications, None means same as the object threshold
+    #[serde(default)]
+    pub appeal_approved_threshold: Option<u16>,
+    #[serde(default)]
+    pub appeal_rejected_threshold: Option<u16>,
+    /// Assign this number of reviewers to each application, None means open voting
+    #[serde(default)]
+    pub assigned_reviewer_number: Option<u16>,
+    /// Assignee who doesn't vote within deadline is replaced, None means never replaced
+    #[serde(default)]
+    pub assignment_deadline_ms: Option<u64>,
+    /// Criteria reviewers score from 1 to 5 when voting, empty means no rubric
+    #[serde(default)]
+    pub content_rubric: Vec<String>,
+    #[serde(default)]
+    pub asset_rubric: Vec<String>,
+    #[serde(default)]
+    pub reviewer_rubric: Vec<String>,
+    #[serde(default)]
+    pub activity_rubric: Vec<String>,
+    #[serde(default)]
+    pub comment_moderation: CommentModeration,
+    /// Threshold of comments, None means same as the content threshold
+    #[serde(default)]
+    pub comment_approved_threshold: Option<u16>,
+    #[serde(default)]
+    pub comment_rejected_threshold: Option<u16>,
+    /// Comment of author whose decided comments reach at least this reputation score is
+    /// published without review
+    #[serde(default)]
+    pub comment_fast_path_reputation: Option<u8>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -54,9 +203,147 @@ pub struct Review {
     pub reviewer: Owner,
     pub approved: bool,
     pub reason: String,
+    /// Empty for an unscored vote, otherwise one score for each rubric criterion
+    pub scores: Vec<RubricScore>,
     pub created_at: Timestamp,
 }
 
+pub const MIN_RUBRIC_SCORE: u8 = 1;
+pub const MAX_RUBRIC_SCORE: u8 = 5;
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, InputObject, Eq, PartialEq)]
+#[graphql(input_name = "RubricScoreInput")]
+pub struct RubricScore {
+    pub criterion: String,
+    pub score: u8,
+}
+
+/// Average scores are in hundredths, 425 means 4.25
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct RubricAverage {
+    pub criterion: String,
+    pub average: u16,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Assignment {
+    pub reviewer: Owner,
+    pub assigned_at: Timestamp,
+    /// Reviewer didn't vote within deadline and is replaced
+    pub timed_out: bool,
+}
+
+/// Assignees timed out on a pending application and the reviewers assigned to replace them
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
+pub struct Reassignment {
+    pub object_type: ObjectType,
+    pub object_id: String,
+    pub timed_out: Vec<Owner>,
+    pub assigned: Vec<Assignment>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct AssignedApplication {
+    pub object_type: ObjectType,
+    pub object_id: String,
+    pub assigned_at: Timestamp,
+}
+
+/// Object id is the cid of content or asset, candidate owner, or activity id
+#[derive(Debug, Deserialize, Serialize, Clone, InputObject, Eq, PartialEq)]
+pub struct BatchReviewItem {
+    pub object_type: ObjectType,
+    pub object_id: String,
+    pub approved: bool,
+    pub reason: Option<String>,
+    pub scores: Vec<RubricScore>,
+}
+
+/// Error is none if the vote is sent to be applied
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, PartialEq, PartialOrd)]
+pub struct BatchReviewResult {
+    pub object_type: ObjectType,
+    pub object_id: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct PendingFilter {
+    /// Only applications not voted by this reviewer yet
+    pub not_voted_by: Option<Owner>,
+    /// Only applications created before this time
+    pub older_than: Option<Timestamp>,
+    /// Only applications submitted by this owner
+    pub author: Option<Owner>,
+}
+
+#[derive(Debug, Clone, SimpleObject)]
+#[graphql(concrete(name = "ContentPage", params(Content)))]
+#[graphql(concrete(name = "AssetPage", params(Asset)))]
+#[graphql(concrete(name = "ActivityPage", params(Activity)))]
+#[graphql(concrete(name = "ReviewerPage", params(Reviewer)))]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    /// Pass as after to fetch the next page, none on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct ReviewRecord {
+    pub object_type: ObjectType,
+    pub object_id: String,
+    pub status: ReviewStatus,
+    pub review: Review,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Appeal {
+    pub argument: String,
+    /// Votes of the original rejection, current status of the application is the appeal outcome
+    pub original_reviewers: HashMap<Owner, Review>,
+    pub original_approved: u16,
+    pub original_rejected: u16,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct Reputation {
+    pub reviewer: Owner,
+    /// Votes which matched the final outcome
+    pub agreed: u32,
+    /// Votes which did not match the final outcome
+    pub disagreed: u32,
+    /// Smoothed agreement percentage, a new reviewer starts from 50
+    pub score: u8,
+}
+
+impl Reputation {
+    pub fn new(reviewer: Owner) -> Self {
+        Reputation {
+            reviewer,
+            agreed: 0,
+            disagreed: 0,
+            score: 50,
+        }
+    }
+
+    pub fn record(&mut self, agreed: bool) {
+        if agreed {
+            self.agreed = self.agreed.saturating_add(1);
+        } else {
+            self.disagreed = self.disagreed.saturating_add(1);
+        }
+        let agreed = self.agreed as u64 + 1;
+        let total = self.agreed as u64 + self.disagreed as u64 + 2;
+        self.score = (agreed * 100 / total) as u8;
+    }
+
+    /// Weight of the reviewer's vote, never zero so every reviewer still counts
+    pub fn weight(&self) -> u64 {
+        self.score.max(1) as u64
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
 pub struct Reviewer {
     pub chain_id: ChainId,
@@ -66,6 +353,30 @@ pub struct Reviewer {
     pub approved: u16,
     pub rejected: u16,
     pub created_at: Timestamp,
+    pub status: ReviewStatus,
+    /// Suspended reviewer could not review until this time
+    pub suspended_until: Option<Timestamp>,
+    /// Reviewers who could not review this application for conflict of interest
+    pub excluded_reviewers: Vec<Owner>,
+    /// Votes changed or withdrawn before resolution, oldest first
+    pub vote_history: Vec<Review>,
+    /// Reviewers assigned in assignment mode, empty means any reviewer could vote
+    pub assignees: Vec<Assignment>,
+    /// Creation chain block height accepting the submission, seeds reviewer assignment
+    pub submitted_height: BlockHeight,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
+pub struct RemovalProposal {
+    pub reviewer: Owner,
+    pub proposer: Owner,
+    pub reason: String,
+    /// Suspend the reviewer for this duration instead of removing it, every vote must propose
+    /// the same
+    pub suspend_ms: Option<u64>,
+    pub reviewers: HashMap<Owner, Review>,
+    pub approved: u16,
+    pub created_at: Timestamp,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -82,6 +393,22 @@ pub struct Content {
     pub approved: u16,
     pub rejected: u16,
     pub created_at: Timestamp,
+    /// Content is published in feed only after this time once approved
+    pub publish_at: Option<Timestamp>,
+    pub status: ReviewStatus,
+    /// Reviewers who could not review this application for conflict of interest
+    pub excluded_reviewers: Vec<Owner>,
+    /// Votes changed or withdrawn before resolution, oldest first
+    pub vote_history: Vec<Review>,
+    /// Reviewers assigned in assignment mode, empty means any reviewer could vote
+    pub assignees: Vec<Assignment>,
+    /// Creation chain block height accepting the submission, seeds reviewer assignment
+    pub submitted_height: BlockHeight,
+    pub appeal: Option<Appeal>,
+    /// Average of each rubric criterion over current votes
+    pub rubric_scores: Vec<RubricAverage>,
+    /// Average of all rubric scores in hundredths, none if nobody scored
+    pub average_score: Option<u16>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -92,10 +419,26 @@ pub struct Asset {
     pub author: Owner,
     pub price: Option<Amount>,
     pub name: String,
+    /// Royalty paid to the author on NFT resales
+    pub royalty_percent: Option<u8>,
     pub reviewers: HashMap<Owner, Review>,
     pub approved: u16,
     pub rejected: u16,
     pub created_at: Timestamp,
+    pub status: ReviewStatus,
+    /// Reviewers who could not review this application for conflict of interest
+    pub excluded_reviewers: Vec<Owner>,
+    /// Votes changed or withdrawn before resolution, oldest first
+    pub vote_history: Vec<Review>,
+    /// Reviewers assigned in assignment mode, empty means any reviewer could vote
+    pub assignees: Vec<Assignment>,
+    /// Creation chain block height accepting the submission, seeds reviewer assignment
+    pub submitted_height: BlockHeight,
+    pub appeal: Option<Appeal>,
+    /// Average of each rubric criterion over current votes
+    pub rubric_scores: Vec<RubricAverage>,
+    /// Average of all rubric scores in hundredths, none if nobody scored
+    pub average_score: Option<u16>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SimpleObject, Eq, PartialEq)]
@@ -107,6 +450,16 @@ pub struct Activity {
     pub rejected: u16,
     pub created_at: Timestamp,
     pub reviewers: HashMap<Owner, Review>,
+    pub status: ReviewStatus,
+    /// Reviewers who could not review this application for conflict of interest
+    pub excluded_reviewers: Vec<Owner>,
+    /// Votes changed or withdrawn before resolution, oldest first
+    pub vote_history: Vec<Review>,
+    /// Reviewers assigned in assignment mode, empty means any reviewer could vote
+    pub assignees: Vec<Assignment>,
+    /// Creation chain block height accepting the submission, seeds reviewer assignment
+    pub submitted_height: BlockHeight,
+    pub appeal: Option<Appeal>,
 }
 
 #[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
@@ -120,10 +473,12 @@ pub enum Operation {
     ApproveReviewer {
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectReviewer {
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitContent {
         cid: String,
@@ -131,15 +486,18 @@ pub enum Operation {
         content: String,
         cover: String,
         abbreviation: String,
+        publish_at: Option<Timestamp>,
     },
     ApproveContent {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectContent {
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitComment {
         cid: String,
@@ -149,10 +507,12 @@ pub enum Operation {
     ApproveAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitAsset {
         cid: String,
@@ -160,24 +520,72 @@ pub enum Operation {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
     },
     RequestSubscribe,
     SubmitActivity {
         activity_id: u64,
         activity_host: Owner,
+        /// Chain where the host created the activity, notifications are sent there
+        host_chain_id: ChainId,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
     },
     ApproveActivity {
         activity_id: u64,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectActivity {
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
     },
     ActivityApproved {
         activity_id: u64,
     },
+    ExpireApplications,
+    SubmitDraft {
+        cid: String,
+        publish_at: Option<Timestamp>,
+    },
+    MarkRead {
+        ids: Vec<u64>,
+    },
+    Resign,
+    ProposeRemoval {
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+    },
+    /// Reviewer declares a relationship with owner, then won't review owner's applications
+    DeclareConflict {
+        owner: Owner,
+    },
+    /// Object id is the cid of content or asset, candidate owner, or activity id
+    ChangeVote {
+        object_type: ObjectType,
+        object_id: String,
+        approved: bool,
+        reason: Option<String>,
+        scores: Vec<RubricScore>,
+    },
+    WithdrawVote {
+        object_type: ObjectType,
+        object_id: String,
+    },
+    /// Owner of a rejected content, asset or activity could appeal once
+    Appeal {
+        object_type: ObjectType,
+        id: String,
+        argument: String,
+    },
+    /// Replace assignees who didn't vote within assignment deadline
+    ReassignReviewers,
+    /// Invalid votes are reported per item and skipped instead of failing the block
+    BatchReview {
+        items: Vec<BatchReviewItem>,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -195,10 +603,12 @@ pub enum Message {
     ApproveReviewer {
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectReviewer {
         candidate: Owner,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitContent {
         cid: String,
@@ -206,15 +616,18 @@ pub enum Message {
         content: String,
         cover: String,
         abbreviation: String,
+        publish_at: Option<Timestamp>,
     },
     ApproveContent {
         content_cid: String,
         reason_cid: Option<String>,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectContent {
         content_cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitComment {
         cid: String,
@@ -224,10 +637,12 @@ pub enum Message {
     ApproveAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectAsset {
         cid: String,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     SubmitAsset {
         cid: String,
@@ -235,6 +650,7 @@ pub enum Message {
         uris: Vec<String>,
         price: Option<Amount>,
         name: String,
+        royalty_percent: Option<u8>,
     },
     RequestSubscribe,
     InstantiationArgument {
@@ -243,15 +659,64 @@ pub enum Message {
     SubmitActivity {
         activity_id: u64,
         activity_host: Owner,
+        /// Chain where the host created the activity, notifications are sent there
+        host_chain_id: ChainId,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
     },
     ApproveActivity {
         activity_id: u64,
         reason: Option<String>,
+        scores: Vec<RubricScore>,
     },
     RejectActivity {
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
+    },
+    ExpireApplications,
+    /// Applications and removal proposals expired at creation chain time, subscribers don't
+    /// check deadlines
+    ApplicationsExpired {
+        applications: Vec<(ObjectType, String)>,
+        removal_proposals: Vec<Owner>,
+    },
+    Notify {
+        owner: Owner,
+        notification: Notification,
+    },
+    Resign,
+    ProposeRemoval {
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+    },
+    DeclareConflict {
+        owner: Owner,
+    },
+    ChangeVote {
+        object_type: ObjectType,
+        object_id: String,
+        approved: bool,
+        reason: Option<String>,
+        scores: Vec<RubricScore>,
+    },
+    WithdrawVote {
+        object_type: ObjectType,
+        object_id: String,
+    },
+    Appeal {
+        object_type: ObjectType,
+        id: String,
+        argument: String,
+    },
+    ReassignReviewers,
+    BatchReview {
+        items: Vec<BatchReviewItem>,
+    },
+    /// Reassignments decided with creation chain time, subscribers don't check deadlines
+    ReviewersReassigned {
+        reassignments: Vec<Reassignment>,
     },
 }
 
@@ -296,4 +761,88 @@ pub enum ReviewError {
 
     #[error("Invalid message id")]
     InvalidMessageId,
+
+    #[error("Invalid draft")]
+    InvalidDraft,
+
+    #[error("Application is not pending")]
+    NotPending,
+
+    #[error("Invalid notification")]
+    InvalidNotification,
+
+    #[error("Last reviewer could not leave")]
+    LastReviewer,
+
+    #[error("Conflict of interest")]
+    ConflictOfInterest,
+
+    #[error("Not reviewed")]
+    NotReviewed,
+
+    #[error("Invalid appeal")]
+    InvalidAppeal,
+
+    #[error("Not assigned")]
+    NotAssigned,
+
+    #[error("Invalid rubric score")]
+    InvalidRubricScore,
+
+    #[error("Invalid royalty")]
+    InvalidRoyalty,
+
+    #[error("Invalid decision policy")]
+    InvalidDecisionPolicy,
+
+    #[error("Review deadline passed")]
+    ReviewExpired,
+
+    #[error("Invalid removal proposal")]
+    InvalidProposal,
+
+    #[error("No reviewer left to review the appeal")]
+    NoAppealReviewer,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use linera_sdk::base::Owner;
+
+    use super::Reputation;
+
+    fn reputation() -> Reputation {
+        Reputation::new(Owner::from_str(&format!("{:064x}", 1)).unwrap())
+    }
+
+    #[test]
+    fn new_reviewer_starts_neutral() {
+        let reputation = reputation();
+        assert_eq!(reputation.score, 50);
+        assert_eq!(reputation.weight(), 50);
+    }
+
+    #[test]
+    fn score_follows_agreement() {
+        let mut reputation = reputation();
+        reputation.record(true);
+        assert_eq!(reputation.score, 66);
+        reputation.record(true);
+        assert_eq!(reputation.score, 75);
+        reputation.record(false);
+        assert_eq!(reputation.score, 60);
+        assert_eq!((reputation.agreed, reputation.disagreed), (2, 1));
+    }
+
+    #[test]
+    fn weight_never_zero() {
+        let mut reputation = reputation();
+        for _ in 0..1000 {
+            reputation.record(false);
+        }
+        assert_eq!(reputation.score, 0);
+        assert_eq!(reputation.weight(), 1);
+    }
 }
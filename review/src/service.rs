@@ -3,11 +3,17 @@
 mod state;
 
 use self::state::Review;
-use async_graphql::{EmptySubscription, Request, Response, Schema};
+use async_graphql::{EmptySubscription, MergedObject, Object, Request, Response, Schema};
 use linera_sdk::{
-    base::WithServiceAbi, graphql::GraphQLMutationRoot, views::View, Service, ServiceRuntime,
+    base::{Owner, WithServiceAbi},
+    graphql::GraphQLMutationRoot,
+    views::View,
+    Service, ServiceRuntime,
+};
+use review::{
+    Activity, Asset, AssignedApplication, Content, Notification, Operation, Page, PendingFilter,
+    ReviewError, ReviewParameters, ReviewRecord, Reviewer,
 };
-use review::{Operation, ReviewParameters};
 use std::sync::Arc;
 
 pub struct ReviewService {
@@ -20,6 +26,81 @@ impl WithServiceAbi for ReviewService {
     type Abi = review::ReviewAbi;
 }
 
+struct ReviewQuery {
+    state: Arc<Review>,
+}
+
+#[Object]
+impl ReviewQuery {
+    async fn notifications(
+        &self,
+        owner: Owner,
+        unread_only: Option<bool>,
+    ) -> Result<Vec<Notification>, ReviewError> {
+        self.state
+            .notifications(owner, unread_only.unwrap_or_default())
+            .await
+    }
+
+    async fn assigned_queue(
+        &self,
+        reviewer: Owner,
+    ) -> Result<Vec<AssignedApplication>, ReviewError> {
+        self.state.assigned_queue(reviewer).await
+    }
+
+    async fn pending_contents(
+        &self,
+        filter: Option<PendingFilter>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Content>, ReviewError> {
+        self.state
+            .pending_contents(filter.unwrap_or_default(), after, first)
+            .await
+    }
+
+    async fn pending_assets(
+        &self,
+        filter: Option<PendingFilter>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Asset>, ReviewError> {
+        self.state
+            .pending_assets(filter.unwrap_or_default(), after, first)
+            .await
+    }
+
+    async fn pending_activities(
+        &self,
+        filter: Option<PendingFilter>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Activity>, ReviewError> {
+        self.state
+            .pending_activities(filter.unwrap_or_default(), after, first)
+            .await
+    }
+
+    async fn pending_reviewers(
+        &self,
+        filter: Option<PendingFilter>,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Reviewer>, ReviewError> {
+        self.state
+            .pending_reviewers(filter.unwrap_or_default(), after, first)
+            .await
+    }
+
+    async fn review_history(&self, reviewer: Owner) -> Result<Vec<ReviewRecord>, ReviewError> {
+        self.state.review_history(reviewer).await
+    }
+}
+
+#[derive(MergedObject)]
+struct QueryRoot(Arc<Review>, ReviewQuery);
+
 impl Service for ReviewService {
     type Parameters = ReviewParameters;
 
@@ -34,7 +115,12 @@ impl Service for ReviewService {
 
     async fn handle_query(&self, request: Request) -> Response {
         let schema = Schema::build(
-            self.state.clone(),
+            QueryRoot(
+                self.state.clone(),
+                ReviewQuery {
+                    state: self.state.clone(),
+                },
+            ),
             Operation::mutation_root(),
             EmptySubscription,
         )
@@ -1,13 +1,26 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use async_graphql::SimpleObject;
 use linera_sdk::{
-    base::{Amount, ChainId, Owner, Timestamp},
+    base::{Amount, BcsHashable, BlockHeight, ChainId, CryptoHash, Owner, Timestamp},
     views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext},
 };
 use review::{
-    Activity, Asset, Content, InstantiationArgument, Review as _Review, ReviewError, Reviewer,
+    Activity, Appeal, Asset, AssignedApplication, Assignment, CommentModeration, Content,
+    DecisionMode, DecisionPolicy, InstantiationArgument, Notification, ObjectType, Page,
+    PendingFilter, Reassignment, RemovalProposal, Reputation, Review as _Review, ReviewError,
+    ReviewRecord, ReviewStatus, Reviewer, RubricAverage, RubricScore, MAX_RUBRIC_SCORE,
+    MIN_RUBRIC_SCORE,
 };
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Oldest notifications are pruned beyond this, read ones first
+const MAX_INBOX_SIZE: usize = 200;
+/// Page size of paginated queries when first is not given
+const DEFAULT_PAGE_SIZE: usize = 20;
 
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
@@ -26,6 +39,308 @@ pub struct Review {
     pub activity_applications: MapView<u64, Activity>,
     pub activity_approved_threshold: RegisterView<u16>,
     pub activity_rejected_threshold: RegisterView<u16>,
+    pub reputations: MapView<Owner, Reputation>,
+    pub content_decision: RegisterView<DecisionPolicy>,
+    pub asset_decision: RegisterView<DecisionPolicy>,
+    pub reviewer_decision: RegisterView<DecisionPolicy>,
+    pub activity_decision: RegisterView<DecisionPolicy>,
+    pub content_review_deadline_ms: RegisterView<Option<u64>>,
+    pub asset_review_deadline_ms: RegisterView<Option<u64>>,
+    pub reviewer_review_deadline_ms: RegisterView<Option<u64>>,
+    pub activity_review_deadline_ms: RegisterView<Option<u64>>,
+    /// Chain where the user submits applications, notifications are sent there
+    pub user_chains: MapView<Owner, ChainId>,
+    pub inbox: MapView<Owner, Vec<Notification>>,
+    pub removal_proposals: MapView<Owner, RemovalProposal>,
+    /// Owner to reviewers who declared a relationship with the owner
+    pub conflicts: MapView<Owner, Vec<Owner>>,
+    pub appeal_approved_threshold: RegisterView<Option<u16>>,
+    pub appeal_rejected_threshold: RegisterView<Option<u16>>,
+    pub assigned_reviewer_number: RegisterView<Option<u16>>,
+    pub assignment_deadline_ms: RegisterView<Option<u64>>,
+    pub content_rubric: RegisterView<Vec<String>>,
+    pub asset_rubric: RegisterView<Vec<String>>,
+    pub reviewer_rubric: RegisterView<Vec<String>>,
+    pub activity_rubric: RegisterView<Vec<String>>,
+    pub comment_moderation: RegisterView<CommentModeration>,
+    pub comment_approved_threshold: RegisterView<Option<u16>>,
+    pub comment_rejected_threshold: RegisterView<Option<u16>>,
+    pub comment_fast_path_reputation: RegisterView<Option<u8>>,
+    /// Sum of all reviewers' reputation weight, kept up to date as reviewers and reputations
+    /// change
+    pub reviewer_weight: RegisterView<u64>,
+    /// Next notification id of each inbox, ids are never reused after pruning
+    pub inbox_sequences: MapView<Owner, u64>,
+    /// Decided comments of each author, approved ones count as agreed
+    pub comment_reputations: MapView<Owner, Reputation>,
+}
+
+/// Each reviewer gets a deterministic rank for an application, lowest ranks are assigned.
+/// Block time is not part of the seed, so every chain replaying the message picks the same ones.
+/// The creation chain height of the submission is part of it, so the author could not grind the
+/// object id to pick the assignees.
+#[derive(Debug, Deserialize, Serialize)]
+struct AssignmentSeed {
+    object_type: ObjectType,
+    object_id: String,
+    height: BlockHeight,
+    round: u32,
+    reviewer: Owner,
+}
+
+impl BcsHashable for AssignmentSeed {}
+
+fn overdue(created_at: Timestamp, deadline_ms: Option<u64>, now: Timestamp) -> bool {
+    match deadline_ms {
+        Some(deadline_ms) => {
+            now.micros()
+                > created_at
+                    .micros()
+                    .saturating_add(deadline_ms.saturating_mul(1000))
+        }
+        None => false,
+    }
+}
+
+/// Move the reviewer's vote to history so the reviewer could vote again
+fn withdraw_review(
+    reviews: &mut HashMap<Owner, _Review>,
+    vote_history: &mut Vec<_Review>,
+    approved: &mut u16,
+    rejected: &mut u16,
+    reviewer: Owner,
+) -> Result<(), ReviewError> {
+    let review = reviews.remove(&reviewer).ok_or(ReviewError::NotReviewed)?;
+    if review.approved {
+        *approved = approved.saturating_sub(1);
+    } else {
+        *rejected = rejected.saturating_sub(1);
+    }
+    vote_history.push(review);
+    Ok(())
+}
+
+/// Appealed application is reviewed again from the appeal time
+fn review_started_at(appeal: &Option<Appeal>, created_at: Timestamp) -> Timestamp {
+    appeal
+        .as_ref()
+        .map_or(created_at, |appeal| appeal.created_at)
+}
+
+/// In assignment mode only active assignees could vote
+fn validate_assignment(reviewer: Owner, assignees: &[Assignment]) -> Result<(), ReviewError> {
+    if assignees.is_empty()
+        || assignees
+            .iter()
+            .any(|assignment| assignment.reviewer == reviewer && !assignment.timed_out)
+    {
+        return Ok(());
+    }
+    Err(ReviewError::NotAssigned)
+}
+
+/// Time out assignees who didn't vote within deadline, return the timed out reviewers
+fn time_out_assignees(
+    assignees: &mut [Assignment],
+    reviews: &HashMap<Owner, _Review>,
+    deadline_ms: Option<u64>,
+    now: Timestamp,
+) -> Vec<Owner> {
+    let mut timed_out = Vec::new();
+    for assignment in assignees.iter_mut() {
+        if assignment.timed_out
+            || reviews.contains_key(&assignment.reviewer)
+            || !overdue(assignment.assigned_at, deadline_ms, now)
+        {
+            continue;
+        }
+        assignment.timed_out = true;
+        timed_out.push(assignment.reviewer);
+    }
+    timed_out
+}
+
+fn reassign_assignees(assignees: &mut Vec<Assignment>, reassignment: &Reassignment) {
+    for assignment in assignees.iter_mut() {
+        if reassignment.timed_out.contains(&assignment.reviewer) {
+            assignment.timed_out = true;
+        }
+    }
+    assignees.extend(reassignment.assigned.iter().cloned());
+}
+
+fn assigned_application(
+    reviewer: Owner,
+    object_type: ObjectType,
+    object_id: String,
+    assignees: &[Assignment],
+    reviews: &HashMap<Owner, _Review>,
+) -> Option<AssignedApplication> {
+    if reviews.contains_key(&reviewer) {
+        return None;
+    }
+    assignees
+        .iter()
+        .find(|assignment| assignment.reviewer == reviewer && !assignment.timed_out)
+        .map(|assignment| AssignedApplication {
+            object_type,
+            object_id,
+            assigned_at: assignment.assigned_at,
+        })
+}
+
+/// Scored votes must score each rubric criterion once, a vote without scores is always valid
+fn validate_scores(rubric: &[String], scores: &[RubricScore]) -> Result<(), ReviewError> {
+    if scores.is_empty() {
+        return Ok(());
+    }
+    if scores.len() != rubric.len() {
+        return Err(ReviewError::InvalidRubricScore);
+    }
+    for criterion in rubric {
+        if scores
+            .iter()
+            .filter(|score| &score.criterion == criterion)
+            .count()
+            != 1
+        {
+            return Err(ReviewError::InvalidRubricScore);
+        }
+    }
+    if scores
+        .iter()
+        .any(|score| !(MIN_RUBRIC_SCORE..=MAX_RUBRIC_SCORE).contains(&score.score))
+    {
+        return Err(ReviewError::InvalidRubricScore);
+    }
+    Ok(())
+}
+
+/// Average each rubric criterion and all scores over current votes, in hundredths
+fn aggregate_scores(
+    rubric: &[String],
+    reviews: &HashMap<Owner, _Review>,
+) -> (Vec<RubricAverage>, Option<u16>) {
+    let mut averages = Vec::new();
+    let mut total = 0u32;
+    let mut count = 0u32;
+    for criterion in rubric {
+        let scores = reviews
+            .values()
+            .flat_map(|review| review.scores.iter())
+            .filter(|score| &score.criterion == criterion)
+            .map(|score| score.score as u32)
+            .collect::<Vec<_>>();
+        if scores.is_empty() {
+            continue;
+        }
+        let sum = scores.iter().sum::<u32>();
+        averages.push(RubricAverage {
+            criterion: criterion.clone(),
+            average: (sum * 100 / scores.len() as u32) as u16,
+        });
+        total += sum;
+        count += scores.len() as u32;
+    }
+    let average = match count {
+        0 => None,
+        _ => Some((total * 100 / count) as u16),
+    };
+    (averages, average)
+}
+
+fn pending_matched(
+    filter: &PendingFilter,
+    status: &ReviewStatus,
+    author: Owner,
+    created_at: Timestamp,
+    reviews: &HashMap<Owner, _Review>,
+) -> bool {
+    *status == ReviewStatus::Pending
+        && filter
+            .not_voted_by
+            .map_or(true, |reviewer| !reviews.contains_key(&reviewer))
+        && filter.older_than.map_or(true, |time| created_at < time)
+        && filter.author.map_or(true, |owner| owner == author)
+}
+
+/// Page items in key order. Cursor is the encoded key of the last item of a page, paging resumes
+/// from the first key greater than it so it still works after that item is gone. Return the
+/// page and the cursor of the next page, none on the last page.
+pub fn paginate<K, T>(
+    mut items: Vec<(K, T)>,
+    after: Option<String>,
+    first: Option<usize>,
+) -> Result<(Vec<T>, Option<String>), serde_json::Error>
+where
+    K: Ord + Serialize + DeserializeOwned,
+{
+    let after = match after {
+        Some(after) => Some(serde_json::from_str::<K>(&after)?),
+        None => None,
+    };
+    let first = first.unwrap_or(DEFAULT_PAGE_SIZE);
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut page = Vec::new();
+    let mut has_more = false;
+    for (key, item) in items {
+        if after.as_ref().map_or(false, |after| key <= *after) {
+            continue;
+        }
+        if page.len() == first {
+            has_more = true;
+            break;
+        }
+        page.push((key, item));
+    }
+    let next_cursor = match page.last() {
+        Some((key, _)) if has_more => Some(serde_json::to_string(key)?),
+        _ => None,
+    };
+    Ok((
+        page.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+    ))
+}
+
+/// Author without any decided comment always goes through review
+fn fast_path_comment(history: Option<Reputation>, level: u8) -> bool {
+    history.map_or(false, |history| history.score >= level)
+}
+
+fn review_records(
+    reviewer: Owner,
+    object_type: ObjectType,
+    object_id: String,
+    status: &ReviewStatus,
+    reviews: &HashMap<Owner, _Review>,
+) -> Option<ReviewRecord> {
+    reviews.get(&reviewer).map(|review| ReviewRecord {
+        object_type,
+        object_id,
+        status: *status,
+        review: review.clone(),
+    })
+}
+
+pub(crate) fn reviewer_object_id(object_id: &str) -> Result<Owner, ReviewError> {
+    Owner::from_str(object_id).map_err(|_| ReviewError::InvalidReviewer)
+}
+
+pub(crate) fn activity_object_id(object_id: &str) -> Result<u64, ReviewError> {
+    object_id
+        .parse::<u64>()
+        .map_err(|_| ReviewError::InvalidActivity)
+}
+
+/// Votes reach the threshold, or every eligible reviewer voted the same way
+fn count_decided(count: u16, threshold: u16, eligible: usize) -> bool {
+    count > 0 && (count >= threshold || count as usize >= eligible)
+}
+
+/// Vote weight exceeds the percent of total weight of eligible reviewers
+fn weight_decided(weight: u64, total_weight: u64, percent: u8) -> bool {
+    weight.saturating_mul(100) > total_weight.saturating_mul(percent as u64)
 }
 
 #[allow(dead_code)]
@@ -50,6 +365,41 @@ impl Review {
             .set(argument.activity_approved_threshold);
         self.activity_rejected_threshold
             .set(argument.activity_rejected_threshold);
+        argument.content_decision.validate()?;
+        argument.asset_decision.validate()?;
+        argument.reviewer_decision.validate()?;
+        argument.activity_decision.validate()?;
+        self.content_decision.set(argument.content_decision);
+        self.asset_decision.set(argument.asset_decision);
+        self.reviewer_decision.set(argument.reviewer_decision);
+        self.activity_decision.set(argument.activity_decision);
+        self.content_review_deadline_ms
+            .set(argument.content_review_deadline_ms);
+        self.asset_review_deadline_ms
+            .set(argument.asset_review_deadline_ms);
+        self.reviewer_review_deadline_ms
+            .set(argument.reviewer_review_deadline_ms);
+        self.activity_review_deadline_ms
+            .set(argument.activity_review_deadline_ms);
+        self.appeal_approved_threshold
+            .set(argument.appeal_approved_threshold);
+        self.appeal_rejected_threshold
+            .set(argument.appeal_rejected_threshold);
+        self.assigned_reviewer_number
+            .set(argument.assigned_reviewer_number);
+        self.assignment_deadline_ms
+            .set(argument.assignment_deadline_ms);
+        self.content_rubric.set(argument.content_rubric);
+        self.asset_rubric.set(argument.asset_rubric);
+        self.reviewer_rubric.set(argument.reviewer_rubric);
+        self.activity_rubric.set(argument.activity_rubric);
+        self.comment_moderation.set(argument.comment_moderation);
+        self.comment_approved_threshold
+            .set(argument.comment_approved_threshold);
+        self.comment_rejected_threshold
+            .set(argument.comment_rejected_threshold);
+        self.comment_fast_path_reputation
+            .set(argument.comment_fast_path_reputation);
         Ok(())
     }
 
@@ -65,6 +415,26 @@ impl Review {
             reviewer_rejected_threshold: *self.reviewer_rejected_threshold.get(),
             activity_approved_threshold: *self.activity_approved_threshold.get(),
             activity_rejected_threshold: *self.activity_rejected_threshold.get(),
+            content_decision: *self.content_decision.get(),
+            asset_decision: *self.asset_decision.get(),
+            reviewer_decision: *self.reviewer_decision.get(),
+            activity_decision: *self.activity_decision.get(),
+            content_review_deadline_ms: *self.content_review_deadline_ms.get(),
+            asset_review_deadline_ms: *self.asset_review_deadline_ms.get(),
+            reviewer_review_deadline_ms: *self.reviewer_review_deadline_ms.get(),
+            activity_review_deadline_ms: *self.activity_review_deadline_ms.get(),
+            appeal_approved_threshold: *self.appeal_approved_threshold.get(),
+            appeal_rejected_threshold: *self.appeal_rejected_threshold.get(),
+            assigned_reviewer_number: *self.assigned_reviewer_number.get(),
+            assignment_deadline_ms: *self.assignment_deadline_ms.get(),
+            content_rubric: self.content_rubric.get().clone(),
+            asset_rubric: self.asset_rubric.get().clone(),
+            reviewer_rubric: self.reviewer_rubric.get().clone(),
+            activity_rubric: self.activity_rubric.get().clone(),
+            comment_moderation: *self.comment_moderation.get(),
+            comment_approved_threshold: *self.comment_approved_threshold.get(),
+            comment_rejected_threshold: *self.comment_rejected_threshold.get(),
+            comment_fast_path_reputation: *self.comment_fast_path_reputation.get(),
         })
     }
 
@@ -84,9 +454,17 @@ impl Review {
                 approved: 1,
                 rejected: 0,
                 created_at: now,
+                status: ReviewStatus::Approved,
+                suspended_until: None,
+                excluded_reviewers: Vec::new(),
+                vote_history: Vec::new(),
+                assignees: Vec::new(),
+                submitted_height: BlockHeight::ZERO,
             },
         )?;
         self.reviewer_number.set(1);
+        self.reviewer_weight
+            .set(self.reputation(creator).await?.weight());
         Ok(())
     }
 
@@ -94,6 +472,11 @@ impl Review {
         &mut self,
         reviewer: Reviewer,
     ) -> Result<(), ReviewError> {
+        if !self.is_reviewer(reviewer.reviewer).await? {
+            let weight = self.reputation(reviewer.reviewer).await?.weight();
+            self.reviewer_weight
+                .set(*self.reviewer_weight.get() + weight);
+        }
         self.reviewers
             .insert(&reviewer.clone().reviewer, reviewer)?;
         Ok(())
@@ -106,20 +489,114 @@ impl Review {
         }
     }
 
+    pub(crate) async fn is_active_reviewer(
+        &self,
+        owner: Owner,
+        now: Timestamp,
+    ) -> Result<bool, ReviewError> {
+        match self.reviewers.get(&owner).await? {
+            Some(reviewer) => Ok(reviewer
+                .suspended_until
+                .map_or(true, |suspended_until| suspended_until <= now)),
+            _ => Ok(false),
+        }
+    }
+
+    /// Reviewers who could vote now, suspended reviewers and excluded owners are not counted
+    pub(crate) async fn eligible_reviewers(
+        &self,
+        excluded: &[Owner],
+        now: Timestamp,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let mut eligible = Vec::new();
+        for reviewer in self.reviewers.indices().await? {
+            if excluded.contains(&reviewer) || !self.is_active_reviewer(reviewer, now).await? {
+                continue;
+            }
+            eligible.push(reviewer);
+        }
+        Ok(eligible)
+    }
+
+    /// Owner, reviewers who declared a relationship with owner and the related owners
+    pub(crate) async fn excluded_reviewers(
+        &self,
+        owner: Owner,
+        related: Vec<Owner>,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let mut excluded = vec![owner];
+        let conflicts = self.conflicts.get(&owner).await?.unwrap_or_default();
+        for reviewer in conflicts.into_iter().chain(related) {
+            if !excluded.contains(&reviewer) {
+                excluded.push(reviewer);
+            }
+        }
+        Ok(excluded)
+    }
+
+    /// Relationship declared after the application is submitted is also respected
+    pub(crate) async fn validate_conflict(
+        &self,
+        reviewer: Owner,
+        owner: Owner,
+        excluded_reviewers: &[Owner],
+    ) -> Result<(), ReviewError> {
+        if excluded_reviewers.contains(&reviewer) {
+            return Err(ReviewError::ConflictOfInterest);
+        }
+        match self.conflicts.get(&owner).await? {
+            Some(conflicts) if conflicts.contains(&reviewer) => {
+                Err(ReviewError::ConflictOfInterest)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) async fn declare_conflict(
+        &mut self,
+        reviewer: Owner,
+        owner: Owner,
+    ) -> Result<(), ReviewError> {
+        if !self.is_reviewer(reviewer).await? || reviewer == owner {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let mut conflicts = self.conflicts.get(&owner).await?.unwrap_or_default();
+        if !conflicts.contains(&reviewer) {
+            conflicts.push(reviewer);
+        }
+        self.conflicts.insert(&owner, conflicts)?;
+        Ok(())
+    }
+
     pub(crate) async fn apply_reviewer(
         &mut self,
         chain_id: ChainId,
         owner: Owner,
         resume: String,
+        height: BlockHeight,
         now: Timestamp,
     ) -> Result<(), ReviewError> {
         if self.is_reviewer(owner).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.reviewer_applications.get(&owner).await? {
-            Some(_) => return Err(ReviewError::InvalidReviewer),
+            // Candidate could apply again once the former application expired
+            Some(reviewer) if reviewer.status != ReviewStatus::Expired => {
+                return Err(ReviewError::InvalidReviewer)
+            }
             _ => {}
         }
+        let excluded_reviewers = self.excluded_reviewers(owner, Vec::new()).await?;
+        let assignees = self
+            .assign_reviewers(
+                ObjectType::Reviewer,
+                owner.to_string(),
+                height,
+                &excluded_reviewers,
+                &[],
+                now,
+            )
+            .await?;
         self.reviewer_applications.insert(
             &owner,
             Reviewer {
@@ -130,6 +607,12 @@ impl Review {
                 approved: 0,
                 rejected: 0,
                 created_at: now,
+                status: ReviewStatus::Pending,
+                suspended_until: None,
+                excluded_reviewers,
+                vote_history: Vec::new(),
+                assignees,
+                submitted_height: height,
             },
         )?;
         Ok(())
@@ -162,15 +645,24 @@ impl Review {
         &self,
         reviewer: Owner,
         candidate: Owner,
+        now: Timestamp,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer, now).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.reviewer_applications.get(&candidate).await? {
-            Some(_reviewer) => match _reviewer.reviewers.get(&reviewer) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Some(_reviewer) => {
+                if _reviewer.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                self.validate_conflict(reviewer, candidate, &_reviewer.excluded_reviewers)
+                    .await?;
+                validate_assignment(reviewer, &_reviewer.assignees)?;
+                match _reviewer.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             None => Err(ReviewError::InvalidReviewer),
         }
     }
@@ -180,13 +672,12 @@ impl Review {
         owner: Owner,
         candidate: Owner,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Reviewer>, ReviewError> {
-        if owner == candidate {
-            return Err(ReviewError::InvalidReviewer);
-        }
-        self.validate_reviewer_review(owner, candidate.clone())
+        self.validate_reviewer_review(owner, candidate.clone(), now)
             .await?;
+        validate_scores(self.reviewer_rubric.get(), &scores)?;
         match self.reviewer_applications.get(&candidate).await? {
             Some(mut reviewer) => {
                 reviewer.approved += 1;
@@ -196,6 +687,7 @@ impl Review {
                         reviewer: owner,
                         approved: true,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -204,13 +696,30 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.reviewer_applications.get(&candidate).await? {
-            Some(reviewer) => {
+            Some(mut reviewer) => {
                 let approved_threshold = *self.reviewer_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if reviewer.approved >= approved_threshold || reviewer.approved >= reviewer_number {
+                let policy = *self.reviewer_decision.get();
+                let eligible = self
+                    .decision_reviewers(&reviewer.assignees, &reviewer.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .decided(
+                        &reviewer.reviewers,
+                        true,
+                        approved_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    let reviewer_number = *self.reviewer_number.get();
+                    reviewer.status = ReviewStatus::Approved;
                     self.reviewers.insert(&candidate, reviewer.clone())?;
                     self.reviewer_applications.remove(&candidate)?;
                     self.reviewer_number.set(reviewer_number + 1);
+                    let weight = self.reputation(candidate).await?.weight();
+                    self.reviewer_weight
+                        .set(*self.reviewer_weight.get() + weight);
                     return Ok(Some(reviewer));
                 }
             }
@@ -224,13 +733,12 @@ impl Review {
         owner: Owner,
         candidate: Owner,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Reviewer>, ReviewError> {
-        if owner == candidate {
-            return Err(ReviewError::InvalidReviewer);
-        }
-        self.validate_reviewer_review(owner, candidate.clone())
+        self.validate_reviewer_review(owner, candidate.clone(), now)
             .await?;
+        validate_scores(self.reviewer_rubric.get(), &scores)?;
         match self.reviewer_applications.get(&candidate).await? {
             Some(mut reviewer) => {
                 reviewer.rejected += 1;
@@ -238,8 +746,9 @@ impl Review {
                     owner,
                     _Review {
                         reviewer: owner,
-                        approved: true,
+                        approved: false,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
@@ -248,10 +757,25 @@ impl Review {
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.reviewer_applications.get(&candidate).await? {
-            Some(reviewer) => {
+            Some(mut reviewer) => {
                 let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if reviewer.rejected >= rejected_threshold || reviewer.rejected >= reviewer_number {
+                let policy = *self.reviewer_decision.get();
+                let eligible = self
+                    .decision_reviewers(&reviewer.assignees, &reviewer.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .decided(
+                        &reviewer.reviewers,
+                        false,
+                        rejected_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    reviewer.status = ReviewStatus::Rejected;
+                    self.reviewer_applications
+                        .insert(&candidate, reviewer.clone())?;
                     return Ok(Some(reviewer));
                 }
             }
@@ -264,20 +788,40 @@ impl Review {
         &self,
         reviewer: Owner,
         content_cid: String,
+        now: Timestamp,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer, now).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => match content.reviewers.get(&reviewer) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Some(content) => {
+                if content.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                self.validate_conflict(reviewer, content.author, &content.excluded_reviewers)
+                    .await?;
+                validate_assignment(reviewer, &content.assignees)?;
+                match content.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             None => Err(ReviewError::InvalidContent),
         }
     }
 
-    pub(crate) async fn submit_content(&mut self, content: Content) -> Result<(), ReviewError> {
+    pub(crate) async fn submit_content(&mut self, mut content: Content) -> Result<(), ReviewError> {
+        content.excluded_reviewers = self.excluded_reviewers(content.author, Vec::new()).await?;
+        content.assignees = self
+            .assign_reviewers(
+                ObjectType::Content,
+                content.cid.clone(),
+                content.submitted_height,
+                &content.excluded_reviewers,
+                &[],
+                content.created_at,
+            )
+            .await?;
         self.content_applications
             .insert(&content.clone().cid, content)?;
         Ok(())
@@ -288,15 +832,14 @@ impl Review {
         reviewer: Owner,
         content_cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Content>, ReviewError> {
-        self.validate_content_review(reviewer, content_cid.clone())
+        self.validate_content_review(reviewer, content_cid.clone(), now)
             .await?;
+        validate_scores(self.content_rubric.get(), &scores)?;
         match self.content_applications.get(&content_cid).await? {
             Some(mut content) => {
-                if reviewer == content.author {
-                    return Err(ReviewError::InvalidReviewer);
-                }
                 content.approved += 1;
                 content.reviewers.insert(
                     reviewer,
@@ -304,18 +847,42 @@ impl Review {
                         reviewer,
                         approved: true,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
+                (content.rubric_scores, content.average_score) =
+                    aggregate_scores(self.content_rubric.get(), &content.reviewers);
                 self.content_applications.insert(&content_cid, content)?;
             }
             _ => return Err(ReviewError::InvalidContent),
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => {
-                let approved_threshold = *self.reviewer_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if content.approved >= approved_threshold || content.approved >= reviewer_number {
+            Some(mut content) => {
+                let approved_threshold = self.appeal_threshold(
+                    self.content_threshold(content.comment_to_cid.is_some(), true),
+                    true,
+                    content.appeal.is_some(),
+                );
+                let policy = *self.content_decision.get();
+                let eligible = self
+                    .decision_reviewers(&content.assignees, &content.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .reach_decision(
+                        &content.reviewers,
+                        reviewer,
+                        true,
+                        approved_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    content.status = ReviewStatus::Approved;
+                    self.content_applications
+                        .insert(&content_cid, content.clone())?;
+                    self.record_comment(&content, true).await?;
                     return Ok(Some(content));
                 }
             }
@@ -329,15 +896,14 @@ impl Review {
         reviewer: Owner,
         content_cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Content>, ReviewError> {
-        self.validate_content_review(reviewer, content_cid.clone())
+        self.validate_content_review(reviewer, content_cid.clone(), now)
             .await?;
+        validate_scores(self.content_rubric.get(), &scores)?;
         match self.content_applications.get(&content_cid).await? {
             Some(mut content) => {
-                if reviewer == content.author {
-                    return Err(ReviewError::InvalidReviewer);
-                }
                 content.rejected += 1;
                 content.reviewers.insert(
                     reviewer,
@@ -345,18 +911,42 @@ impl Review {
                         reviewer,
                         approved: false,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
+                (content.rubric_scores, content.average_score) =
+                    aggregate_scores(self.content_rubric.get(), &content.reviewers);
                 self.content_applications.insert(&content_cid, content)?;
             }
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.content_applications.get(&content_cid).await? {
-            Some(content) => {
-                let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if content.rejected >= rejected_threshold || content.rejected >= reviewer_number {
+            Some(mut content) => {
+                let rejected_threshold = self.appeal_threshold(
+                    self.content_threshold(content.comment_to_cid.is_some(), false),
+                    false,
+                    content.appeal.is_some(),
+                );
+                let policy = *self.content_decision.get();
+                let eligible = self
+                    .decision_reviewers(&content.assignees, &content.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .reach_decision(
+                        &content.reviewers,
+                        reviewer,
+                        false,
+                        rejected_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    content.status = ReviewStatus::Rejected;
+                    self.content_applications
+                        .insert(&content_cid, content.clone())?;
+                    self.record_comment(&content, false).await?;
                     return Ok(Some(content));
                 }
             }
@@ -369,15 +959,24 @@ impl Review {
         &self,
         reviewer: Owner,
         cid: String,
+        now: Timestamp,
     ) -> Result<(), ReviewError> {
-        if !self.is_reviewer(reviewer).await? {
+        if !self.is_active_reviewer(reviewer, now).await? {
             return Err(ReviewError::InvalidReviewer);
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => match asset.reviewers.get(&reviewer) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Some(asset) => {
+                if asset.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                self.validate_conflict(reviewer, asset.author, &asset.excluded_reviewers)
+                    .await?;
+                validate_assignment(reviewer, &asset.assignees)?;
+                match asset.reviewers.get(&reviewer) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             None => Ok(()),
         }
     }
@@ -387,14 +986,14 @@ impl Review {
         reviewer: Owner,
         cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Asset>, ReviewError> {
-        self.validate_asset_review(reviewer, cid.clone()).await?;
+        self.validate_asset_review(reviewer, cid.clone(), now)
+            .await?;
+        validate_scores(self.asset_rubric.get(), &scores)?;
         match self.asset_applications.get(&cid).await? {
             Some(mut asset) => {
-                if reviewer == asset.author {
-                    return Err(ReviewError::InvalidReviewer);
-                }
                 asset.approved += 1;
                 asset.reviewers.insert(
                     reviewer,
@@ -402,18 +1001,40 @@ impl Review {
                         reviewer,
                         approved: true,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
+                (asset.rubric_scores, asset.average_score) =
+                    aggregate_scores(self.asset_rubric.get(), &asset.reviewers);
                 self.asset_applications.insert(&cid, asset)?;
             }
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => {
-                let approved_threshold = *self.reviewer_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if asset.approved >= approved_threshold || asset.approved >= reviewer_number {
+            Some(mut asset) => {
+                let approved_threshold = self.appeal_threshold(
+                    *self.asset_approved_threshold.get(),
+                    true,
+                    asset.appeal.is_some(),
+                );
+                let policy = *self.asset_decision.get();
+                let eligible = self
+                    .decision_reviewers(&asset.assignees, &asset.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .reach_decision(
+                        &asset.reviewers,
+                        reviewer,
+                        true,
+                        approved_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    asset.status = ReviewStatus::Approved;
+                    self.asset_applications.insert(&cid, asset.clone())?;
                     return Ok(Some(asset));
                 }
             }
@@ -427,14 +1048,14 @@ impl Review {
         reviewer: Owner,
         cid: String,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Asset>, ReviewError> {
-        self.validate_asset_review(reviewer, cid.clone()).await?;
+        self.validate_asset_review(reviewer, cid.clone(), now)
+            .await?;
+        validate_scores(self.asset_rubric.get(), &scores)?;
         match self.asset_applications.get(&cid).await? {
             Some(mut asset) => {
-                if reviewer == asset.author {
-                    return Err(ReviewError::InvalidReviewer);
-                }
                 asset.rejected += 1;
                 asset.reviewers.insert(
                     reviewer,
@@ -442,18 +1063,40 @@ impl Review {
                         reviewer,
                         approved: false,
                         reason,
+                        scores,
                         created_at: now,
                     },
                 );
+                (asset.rubric_scores, asset.average_score) =
+                    aggregate_scores(self.asset_rubric.get(), &asset.reviewers);
                 self.asset_applications.insert(&cid, asset)?;
             }
             _ => return Err(ReviewError::InvalidReviewer),
         }
         match self.asset_applications.get(&cid).await? {
-            Some(asset) => {
-                let rejected_threshold = *self.reviewer_rejected_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if asset.rejected >= rejected_threshold || asset.rejected >= reviewer_number {
+            Some(mut asset) => {
+                let rejected_threshold = self.appeal_threshold(
+                    *self.asset_rejected_threshold.get(),
+                    false,
+                    asset.appeal.is_some(),
+                );
+                let policy = *self.asset_decision.get();
+                let eligible = self
+                    .decision_reviewers(&asset.assignees, &asset.excluded_reviewers, now)
+                    .await?;
+                if self
+                    .reach_decision(
+                        &asset.reviewers,
+                        reviewer,
+                        false,
+                        rejected_threshold,
+                        policy,
+                        &eligible,
+                    )
+                    .await?
+                {
+                    asset.status = ReviewStatus::Rejected;
+                    self.asset_applications.insert(&cid, asset.clone())?;
                     return Ok(Some(asset));
                 }
             }
@@ -462,10 +1105,25 @@ impl Review {
         Ok(None)
     }
 
-    pub(crate) async fn submit_asset(&mut self, asset: Asset) -> Result<(), ReviewError> {
+    pub(crate) async fn submit_asset(&mut self, mut asset: Asset) -> Result<(), ReviewError> {
+        if asset.royalty_percent.unwrap_or_default() > market::MAX_ROYALTY_PERCENT {
+            return Err(ReviewError::InvalidRoyalty);
+        }
         match self.asset_applications.get(&asset.clone().cid).await? {
             Some(_) => return Err(ReviewError::AlreadyExists),
             _ => {
+                asset.excluded_reviewers =
+                    self.excluded_reviewers(asset.author, Vec::new()).await?;
+                asset.assignees = self
+                    .assign_reviewers(
+                        ObjectType::Asset,
+                        asset.cid.clone(),
+                        asset.submitted_height,
+                        &asset.excluded_reviewers,
+                        &[],
+                        asset.created_at,
+                    )
+                    .await?;
                 self.asset_applications.insert(&asset.clone().cid, asset)?;
             }
         }
@@ -477,8 +1135,22 @@ impl Review {
         activity_id: u64,
         activity_host: Owner,
         budget_amount: Amount,
+        sponsors: Vec<Owner>,
+        height: BlockHeight,
         now: Timestamp,
     ) -> Result<(), ReviewError> {
+        // Sponsors fund the activity so they could not review it
+        let excluded_reviewers = self.excluded_reviewers(activity_host, sponsors).await?;
+        let assignees = self
+            .assign_reviewers(
+                ObjectType::Activity,
+                activity_id.to_string(),
+                height,
+                &excluded_reviewers,
+                &[],
+                now,
+            )
+            .await?;
         match self.activity_applications.get(&activity_id).await {
             Ok(Some(_)) => Err(ReviewError::AlreadyExists),
             _ => Ok(self.activity_applications.insert(
@@ -491,6 +1163,12 @@ impl Review {
                     rejected: 0,
                     created_at: now,
                     reviewers: HashMap::default(),
+                    status: ReviewStatus::Pending,
+                    excluded_reviewers,
+                    vote_history: Vec::new(),
+                    appeal: None,
+                    assignees,
+                    submitted_height: height,
                 },
             )?),
         }
@@ -500,12 +1178,24 @@ impl Review {
         &self,
         owner: Owner,
         activity_id: u64,
+        now: Timestamp,
     ) -> Result<(), ReviewError> {
+        if !self.is_active_reviewer(owner, now).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
         match self.activity_applications.get(&activity_id).await {
-            Ok(Some(activity)) => match activity.reviewers.get(&owner) {
-                Some(_) => Err(ReviewError::AlreadyReviewed),
-                _ => Ok(()),
-            },
+            Ok(Some(activity)) => {
+                if activity.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                self.validate_conflict(owner, activity.activity_host, &activity.excluded_reviewers)
+                    .await?;
+                validate_assignment(owner, &activity.assignees)?;
+                match activity.reviewers.get(&owner) {
+                    Some(_) => Err(ReviewError::AlreadyReviewed),
+                    _ => Ok(()),
+                }
+            }
             Ok(None) => Err(ReviewError::InvalidActivity),
             Err(err) => Err(ReviewError::ViewError(err)),
         }
@@ -516,9 +1206,12 @@ impl Review {
         owner: Owner,
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Activity>, ReviewError> {
-        self.validate_activity_review(owner, activity_id).await?;
+        self.validate_activity_review(owner, activity_id, now)
+            .await?;
+        validate_scores(self.activity_rubric.get(), &scores)?;
 
         let mut activity = self.activity_applications.get(&activity_id).await?.unwrap();
         activity.reviewers.insert(
@@ -527,6 +1220,7 @@ impl Review {
                 reviewer: owner,
                 approved: true,
                 reason,
+                scores,
                 created_at: now,
             },
         );
@@ -534,10 +1228,29 @@ impl Review {
         self.activity_applications
             .insert(&activity_id, activity.clone())?;
 
-        let approved_threshold = *self.activity_approved_threshold.get();
-        let reviewer_number = *self.reviewer_number.get();
-
-        if activity.approved >= approved_threshold || activity.approved >= reviewer_number {
+        let approved_threshold = self.appeal_threshold(
+            *self.activity_approved_threshold.get(),
+            true,
+            activity.appeal.is_some(),
+        );
+        let policy = *self.activity_decision.get();
+        let eligible = self
+            .decision_reviewers(&activity.assignees, &activity.excluded_reviewers, now)
+            .await?;
+        if self
+            .reach_decision(
+                &activity.reviewers,
+                owner,
+                true,
+                approved_threshold,
+                policy,
+                &eligible,
+            )
+            .await?
+        {
+            activity.status = ReviewStatus::Approved;
+            self.activity_applications
+                .insert(&activity_id, activity.clone())?;
             return Ok(Some(activity));
         }
         Ok(None)
@@ -548,17 +1261,21 @@ impl Review {
         owner: Owner,
         activity_id: u64,
         reason: String,
+        scores: Vec<RubricScore>,
         now: Timestamp,
     ) -> Result<Option<Activity>, ReviewError> {
-        self.validate_activity_review(owner, activity_id).await?;
+        self.validate_activity_review(owner, activity_id, now)
+            .await?;
+        validate_scores(self.activity_rubric.get(), &scores)?;
 
         let mut activity = self.activity_applications.get(&activity_id).await?.unwrap();
         activity.reviewers.insert(
             owner.clone(),
             _Review {
                 reviewer: owner,
-                approved: true,
+                approved: false,
                 reason,
+                scores,
                 created_at: now,
             },
         );
@@ -566,27 +1283,1656 @@ impl Review {
         self.activity_applications
             .insert(&activity_id, activity.clone())?;
 
-        let rejected_threshold = *self.activity_rejected_threshold.get();
-        let reviewer_number = *self.reviewer_number.get();
-
-        if activity.rejected >= rejected_threshold || activity.rejected >= reviewer_number {
+        let rejected_threshold = self.appeal_threshold(
+            *self.activity_rejected_threshold.get(),
+            false,
+            activity.appeal.is_some(),
+        );
+        let policy = *self.activity_decision.get();
+        let eligible = self
+            .decision_reviewers(&activity.assignees, &activity.excluded_reviewers, now)
+            .await?;
+        if self
+            .reach_decision(
+                &activity.reviewers,
+                owner,
+                false,
+                rejected_threshold,
+                policy,
+                &eligible,
+            )
+            .await?
+        {
+            activity.status = ReviewStatus::Rejected;
+            self.activity_applications
+                .insert(&activity_id, activity.clone())?;
             return Ok(Some(activity));
         }
         Ok(None)
     }
 
-    pub(crate) async fn activity_approved(&self, activity_id: u64) -> Result<bool, ReviewError> {
-        match self.activity_applications.get(&activity_id).await {
-            Ok(Some(activity)) => {
-                let approved_threshold = *self.activity_approved_threshold.get();
-                let reviewer_number = *self.reviewer_number.get();
-                if activity.approved >= approved_threshold || activity.approved >= reviewer_number {
-                    return Ok(true);
+    pub(crate) async fn reputation(&self, reviewer: Owner) -> Result<Reputation, ReviewError> {
+        match self.reputations.get(&reviewer).await? {
+            Some(reputation) => Ok(reputation),
+            _ => Ok(Reputation::new(reviewer)),
+        }
+    }
+
+    /// Total weight of the eligible reviewers. Usually most reviewers are eligible, then the
+    /// weight of the others is taken off the cached total instead of summing every reviewer.
+    pub(crate) async fn total_reviewer_weight(
+        &self,
+        eligible: &[Owner],
+    ) -> Result<u64, ReviewError> {
+        if eligible.len() * 2 <= *self.reviewer_number.get() as usize {
+            let mut weight = 0;
+            for reviewer in eligible {
+                weight += self.reputation(*reviewer).await?.weight();
+            }
+            return Ok(weight);
+        }
+        let eligible = eligible.iter().collect::<HashSet<_>>();
+        let mut weight = *self.reviewer_weight.get();
+        for reviewer in self.reviewers.indices().await? {
+            if eligible.contains(&reviewer) {
+                continue;
+            }
+            weight = weight.saturating_sub(self.reputation(reviewer).await?.weight());
+        }
+        Ok(weight)
+    }
+
+    async fn remove_reviewer_weight(&mut self, reviewer: Owner) -> Result<(), ReviewError> {
+        let weight = self.reputation(reviewer).await?.weight();
+        self.reviewer_weight
+            .set(self.reviewer_weight.get().saturating_sub(weight));
+        Ok(())
+    }
+
+    /// Whether votes with the given attitude are enough to resolve the object, thresholds are
+    /// capped by the reviewers eligible to vote on it
+    pub(crate) async fn decided(
+        &self,
+        reviews: &HashMap<Owner, _Review>,
+        approved: bool,
+        threshold: u16,
+        policy: DecisionPolicy,
+        eligible: &[Owner],
+    ) -> Result<bool, ReviewError> {
+        let votes = reviews
+            .values()
+            .filter(|review| review.approved == approved)
+            .collect::<Vec<_>>();
+        match policy.mode {
+            DecisionMode::Count => Ok(count_decided(votes.len() as u16, threshold, eligible.len())),
+            DecisionMode::Weighted => {
+                let percent = if approved {
+                    policy.approved_percent
+                } else {
+                    policy.rejected_percent
+                };
+                let mut weight = 0;
+                for review in votes {
+                    weight += self.reputation(review.reviewer).await?.weight();
                 }
-                Ok(false)
+                let total_weight = self.total_reviewer_weight(eligible).await?;
+                Ok(weight_decided(weight, total_weight, percent))
+            }
+        }
+    }
+
+    /// Resolve the object with the vote just cast, reputations are settled only by the vote
+    /// which reaches the decision
+    pub(crate) async fn reach_decision(
+        &mut self,
+        reviews: &HashMap<Owner, _Review>,
+        voter: Owner,
+        approved: bool,
+        threshold: u16,
+        policy: DecisionPolicy,
+        eligible: &[Owner],
+    ) -> Result<bool, ReviewError> {
+        if !self
+            .decided(reviews, approved, threshold, policy, eligible)
+            .await?
+        {
+            return Ok(false);
+        }
+        let mut previous = reviews.clone();
+        previous.remove(&voter);
+        if !self
+            .decided(&previous, approved, threshold, policy, eligible)
+            .await?
+        {
+            self.update_reputations(reviews, approved).await?;
+        }
+        Ok(true)
+    }
+
+    pub(crate) async fn update_reputations(
+        &mut self,
+        reviews: &HashMap<Owner, _Review>,
+        approved: bool,
+    ) -> Result<(), ReviewError> {
+        for (reviewer, review) in reviews {
+            let mut reputation = self.reputation(*reviewer).await?;
+            let weight = reputation.weight();
+            reputation.record(review.approved == approved);
+            if self.is_reviewer(*reviewer).await? {
+                self.reviewer_weight.set(
+                    (*self.reviewer_weight.get() + reputation.weight()).saturating_sub(weight),
+                );
             }
+            self.reputations.insert(reviewer, reputation)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn activity_approved(&self, activity_id: u64) -> Result<bool, ReviewError> {
+        match self.activity_applications.get(&activity_id).await {
+            Ok(Some(activity)) => Ok(activity.status == ReviewStatus::Approved),
             Ok(None) => Err(ReviewError::InvalidActivity),
             Err(err) => Err(ReviewError::ViewError(err)),
         }
     }
+
+    /// Whether the pending application passed its review deadline. Only creation chain decides
+    /// it with its own time, subscribers follow the expired applications it broadcasts.
+    pub(crate) async fn application_overdue(
+        &self,
+        object_type: ObjectType,
+        object_id: &str,
+        now: Timestamp,
+    ) -> Result<bool, ReviewError> {
+        let (started_at, deadline_ms) = match object_type {
+            ObjectType::Content => match self.content_applications.get(object_id).await? {
+                Some(content) if content.status == ReviewStatus::Pending => (
+                    review_started_at(&content.appeal, content.created_at),
+                    *self.content_review_deadline_ms.get(),
+                ),
+                _ => return Ok(false),
+            },
+            ObjectType::Asset => match self.asset_applications.get(object_id).await? {
+                Some(asset) if asset.status == ReviewStatus::Pending => (
+                    review_started_at(&asset.appeal, asset.created_at),
+                    *self.asset_review_deadline_ms.get(),
+                ),
+                _ => return Ok(false),
+            },
+            ObjectType::Reviewer => {
+                let candidate = reviewer_object_id(object_id)?;
+                match self.reviewer_applications.get(&candidate).await? {
+                    Some(reviewer) if reviewer.status == ReviewStatus::Pending => {
+                        (reviewer.created_at, *self.reviewer_review_deadline_ms.get())
+                    }
+                    _ => return Ok(false),
+                }
+            }
+            ObjectType::Activity => {
+                let activity_id = activity_object_id(object_id)?;
+                match self.activity_applications.get(&activity_id).await? {
+                    Some(activity) if activity.status == ReviewStatus::Pending => (
+                        review_started_at(&activity.appeal, activity.created_at),
+                        *self.activity_review_deadline_ms.get(),
+                    ),
+                    _ => return Ok(false),
+                }
+            }
+        };
+        Ok(overdue(started_at, deadline_ms, now))
+    }
+
+    /// Mark the pending application expired, return its author
+    pub(crate) async fn expire_application(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<Option<Owner>, ReviewError> {
+        match object_type {
+            ObjectType::Content => match self.content_applications.get(&object_id).await? {
+                Some(mut content) if content.status == ReviewStatus::Pending => {
+                    content.status = ReviewStatus::Expired;
+                    self.content_applications
+                        .insert(&object_id, content.clone())?;
+                    Ok(Some(content.author))
+                }
+                _ => Ok(None),
+            },
+            ObjectType::Asset => match self.asset_applications.get(&object_id).await? {
+                Some(mut asset) if asset.status == ReviewStatus::Pending => {
+                    asset.status = ReviewStatus::Expired;
+                    self.asset_applications.insert(&object_id, asset.clone())?;
+                    Ok(Some(asset.author))
+                }
+                _ => Ok(None),
+            },
+            ObjectType::Reviewer => {
+                let candidate = reviewer_object_id(&object_id)?;
+                match self.reviewer_applications.get(&candidate).await? {
+                    Some(mut reviewer) if reviewer.status == ReviewStatus::Pending => {
+                        reviewer.status = ReviewStatus::Expired;
+                        self.reviewer_applications
+                            .insert(&candidate, reviewer.clone())?;
+                        Ok(Some(reviewer.reviewer))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            ObjectType::Activity => {
+                let activity_id = activity_object_id(&object_id)?;
+                match self.activity_applications.get(&activity_id).await? {
+                    Some(mut activity) if activity.status == ReviewStatus::Pending => {
+                        activity.status = ReviewStatus::Expired;
+                        self.activity_applications
+                            .insert(&activity_id, activity.clone())?;
+                        Ok(Some(activity.activity_host))
+                    }
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Overdue pending applications at creation chain time, return object type and object id
+    pub(crate) async fn overdue_applications(
+        &self,
+        now: Timestamp,
+    ) -> Result<Vec<(ObjectType, String)>, ReviewError> {
+        let mut object_ids = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            object_ids.push((ObjectType::Content, cid));
+        }
+        for cid in self.asset_applications.indices().await? {
+            object_ids.push((ObjectType::Asset, cid));
+        }
+        for candidate in self.reviewer_applications.indices().await? {
+            object_ids.push((ObjectType::Reviewer, candidate.to_string()));
+        }
+        for activity_id in self.activity_applications.indices().await? {
+            object_ids.push((ObjectType::Activity, activity_id.to_string()));
+        }
+        let mut overdue = Vec::new();
+        for (object_type, object_id) in object_ids {
+            if self
+                .application_overdue(object_type, &object_id, now)
+                .await?
+            {
+                overdue.push((object_type, object_id));
+            }
+        }
+        Ok(overdue)
+    }
+
+    pub(crate) async fn record_user_chain(
+        &mut self,
+        owner: Owner,
+        chain_id: ChainId,
+    ) -> Result<(), ReviewError> {
+        self.user_chains.insert(&owner, chain_id)?;
+        Ok(())
+    }
+
+    pub(crate) async fn user_chain(&self, owner: Owner) -> Result<Option<ChainId>, ReviewError> {
+        Ok(self.user_chains.get(&owner).await?)
+    }
+
+    pub(crate) async fn notify(
+        &mut self,
+        owner: Owner,
+        mut notification: Notification,
+    ) -> Result<(), ReviewError> {
+        let mut notifications = self.inbox.get(&owner).await?.unwrap_or_default();
+        let id = self.inbox_sequences.get(&owner).await?.unwrap_or_default();
+        notification.id = id;
+        notification.read = false;
+        notifications.push(notification);
+        if notifications.len() > MAX_INBOX_SIZE {
+            let index = notifications
+                .iter()
+                .position(|notification| notification.read)
+                .unwrap_or(0);
+            notifications.remove(index);
+        }
+        self.inbox.insert(&owner, notifications)?;
+        self.inbox_sequences.insert(&owner, id + 1)?;
+        Ok(())
+    }
+
+    pub(crate) async fn mark_read(
+        &mut self,
+        owner: Owner,
+        ids: Vec<u64>,
+    ) -> Result<(), ReviewError> {
+        let mut notifications = self.inbox.get(&owner).await?.unwrap_or_default();
+        for id in ids {
+            match notifications
+                .iter_mut()
+                .find(|notification| notification.id == id)
+            {
+                Some(notification) => notification.read = true,
+                None => return Err(ReviewError::InvalidNotification),
+            }
+        }
+        self.inbox.insert(&owner, notifications)?;
+        Ok(())
+    }
+
+    pub(crate) async fn notifications(
+        &self,
+        owner: Owner,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, ReviewError> {
+        Ok(self
+            .inbox
+            .get(&owner)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|notification| !unread_only || !notification.read)
+            .collect())
+    }
+
+    pub(crate) async fn resign_reviewer(&mut self, owner: Owner) -> Result<(), ReviewError> {
+        if !self.is_reviewer(owner).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let reviewer_number = *self.reviewer_number.get();
+        if reviewer_number <= 1 {
+            return Err(ReviewError::LastReviewer);
+        }
+        self.remove_reviewer_weight(owner).await?;
+        self.reviewers.remove(&owner)?;
+        self.removal_proposals.remove(&owner)?;
+        self.reviewer_number.set(reviewer_number - 1);
+        self.discard_votes(owner).await
+    }
+
+    /// Votes of a reviewer who left are withdrawn from pending applications and removal
+    /// proposals, so they don't count toward any decision
+    pub(crate) async fn discard_votes(&mut self, reviewer: Owner) -> Result<(), ReviewError> {
+        let mut voted = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            if let Some(content) = self.content_applications.get(&cid).await? {
+                if content.status == ReviewStatus::Pending
+                    && content.reviewers.contains_key(&reviewer)
+                {
+                    voted.push((ObjectType::Content, cid));
+                }
+            }
+        }
+        for cid in self.asset_applications.indices().await? {
+            if let Some(asset) = self.asset_applications.get(&cid).await? {
+                if asset.status == ReviewStatus::Pending && asset.reviewers.contains_key(&reviewer)
+                {
+                    voted.push((ObjectType::Asset, cid));
+                }
+            }
+        }
+        for candidate in self.reviewer_applications.indices().await? {
+            if let Some(application) = self.reviewer_applications.get(&candidate).await? {
+                if application.status == ReviewStatus::Pending
+                    && application.reviewers.contains_key(&reviewer)
+                {
+                    voted.push((ObjectType::Reviewer, candidate.to_string()));
+                }
+            }
+        }
+        for activity_id in self.activity_applications.indices().await? {
+            if let Some(activity) = self.activity_applications.get(&activity_id).await? {
+                if activity.status == ReviewStatus::Pending
+                    && activity.reviewers.contains_key(&reviewer)
+                {
+                    voted.push((ObjectType::Activity, activity_id.to_string()));
+                }
+            }
+        }
+        for (object_type, object_id) in voted {
+            self.withdraw_vote(object_type, object_id, reviewer).await?;
+        }
+        for owner in self.removal_proposals.indices().await? {
+            if let Some(mut proposal) = self.removal_proposals.get(&owner).await? {
+                if proposal.reviewers.remove(&reviewer).is_some() {
+                    proposal.approved = proposal.approved.saturating_sub(1);
+                    self.removal_proposals.insert(&owner, proposal)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removal proposal is decided like a reviewer application, so it shares the deadline
+    pub(crate) async fn removal_proposal_overdue(
+        &self,
+        reviewer: Owner,
+        now: Timestamp,
+    ) -> Result<bool, ReviewError> {
+        match self.removal_proposals.get(&reviewer).await? {
+            Some(proposal) => Ok(overdue(
+                proposal.created_at,
+                *self.reviewer_review_deadline_ms.get(),
+                now,
+            )),
+            _ => Ok(false),
+        }
+    }
+
+    pub(crate) async fn overdue_removal_proposals(
+        &self,
+        now: Timestamp,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let mut overdue = Vec::new();
+        for reviewer in self.removal_proposals.indices().await? {
+            if self.removal_proposal_overdue(reviewer, now).await? {
+                overdue.push(reviewer);
+            }
+        }
+        Ok(overdue)
+    }
+
+    pub(crate) async fn expire_removal_proposal(
+        &mut self,
+        reviewer: Owner,
+    ) -> Result<(), ReviewError> {
+        self.removal_proposals.remove(&reviewer)?;
+        Ok(())
+    }
+
+    /// Vote to remove or suspend the reviewer, the first proposal decides whether it's a
+    /// suspension and later votes must agree with it. Return the proposal once it's approved by
+    /// other reviewers.
+    pub(crate) async fn propose_removal(
+        &mut self,
+        owner: Owner,
+        reviewer: Owner,
+        reason: String,
+        suspend_ms: Option<u64>,
+        now: Timestamp,
+    ) -> Result<Option<RemovalProposal>, ReviewError> {
+        if owner == reviewer {
+            return Err(ReviewError::ConflictOfInterest);
+        }
+        if !self.is_active_reviewer(owner, now).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        if !self.is_reviewer(reviewer).await? {
+            return Err(ReviewError::InvalidReviewer);
+        }
+        let mut proposal = match self.removal_proposals.get(&reviewer).await? {
+            Some(proposal) => proposal,
+            _ => RemovalProposal {
+                reviewer,
+                proposer: owner,
+                reason: reason.clone(),
+                suspend_ms,
+                reviewers: HashMap::default(),
+                approved: 0,
+                created_at: now,
+            },
+        };
+        if proposal.suspend_ms != suspend_ms {
+            return Err(ReviewError::InvalidProposal);
+        }
+        if proposal.reviewers.contains_key(&owner) {
+            return Err(ReviewError::AlreadyReviewed);
+        }
+        proposal.approved += 1;
+        proposal.reviewers.insert(
+            owner,
+            _Review {
+                reviewer: owner,
+                approved: true,
+                reason,
+                scores: Vec::new(),
+                created_at: now,
+            },
+        );
+
+        let approved_threshold = *self.reviewer_approved_threshold.get();
+        let policy = *self.reviewer_decision.get();
+        let eligible = self.eligible_reviewers(&[reviewer], now).await?;
+        if !self
+            .decided(
+                &proposal.reviewers,
+                true,
+                approved_threshold,
+                policy,
+                &eligible,
+            )
+            .await?
+        {
+            self.removal_proposals.insert(&reviewer, proposal)?;
+            return Ok(None);
+        }
+
+        self.removal_proposals.remove(&reviewer)?;
+        match proposal.suspend_ms {
+            Some(suspend_ms) => {
+                if let Some(mut _reviewer) = self.reviewers.get(&reviewer).await? {
+                    _reviewer.suspended_until = Some(Timestamp::from(
+                        now.micros().saturating_add(suspend_ms.saturating_mul(1000)),
+                    ));
+                    self.reviewers.insert(&reviewer, _reviewer)?;
+                }
+            }
+            None => {
+                let reviewer_number = *self.reviewer_number.get();
+                self.remove_reviewer_weight(reviewer).await?;
+                self.reviewers.remove(&reviewer)?;
+                self.reviewer_number.set(reviewer_number.saturating_sub(1));
+                self.discard_votes(reviewer).await?;
+            }
+        }
+        Ok(Some(proposal))
+    }
+
+    /// Withdraw the vote on a pending application, return the owner of the application
+    pub(crate) async fn withdraw_vote(
+        &mut self,
+        object_type: ObjectType,
+        object_id: String,
+        reviewer: Owner,
+    ) -> Result<Owner, ReviewError> {
+        match object_type {
+            ObjectType::Content => {
+                let mut content = self
+                    .content_applications
+                    .get(&object_id)
+                    .await?
+                    .ok_or(ReviewError::InvalidContent)?;
+                if content.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                withdraw_review(
+                    &mut content.reviewers,
+                    &mut content.vote_history,
+                    &mut content.approved,
+                    &mut content.rejected,
+                    reviewer,
+                )?;
+                (content.rubric_scores, content.average_score) =
+                    aggregate_scores(self.content_rubric.get(), &content.reviewers);
+                let author = content.author;
+                self.content_applications.insert(&object_id, content)?;
+                Ok(author)
+            }
+            ObjectType::Asset => {
+                let mut asset = self
+                    .asset_applications
+                    .get(&object_id)
+                    .await?
+                    .ok_or(ReviewError::InvalidContent)?;
+                if asset.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                withdraw_review(
+                    &mut asset.reviewers,
+                    &mut asset.vote_history,
+                    &mut asset.approved,
+                    &mut asset.rejected,
+                    reviewer,
+                )?;
+                (asset.rubric_scores, asset.average_score) =
+                    aggregate_scores(self.asset_rubric.get(), &asset.reviewers);
+                let author = asset.author;
+                self.asset_applications.insert(&object_id, asset)?;
+                Ok(author)
+            }
+            ObjectType::Reviewer => {
+                let candidate = reviewer_object_id(&object_id)?;
+                let mut application = self
+                    .reviewer_applications
+                    .get(&candidate)
+                    .await?
+                    .ok_or(ReviewError::InvalidReviewer)?;
+                if application.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                withdraw_review(
+                    &mut application.reviewers,
+                    &mut application.vote_history,
+                    &mut application.approved,
+                    &mut application.rejected,
+                    reviewer,
+                )?;
+                self.reviewer_applications.insert(&candidate, application)?;
+                Ok(candidate)
+            }
+            ObjectType::Activity => {
+                let activity_id = activity_object_id(&object_id)?;
+                let mut activity = self
+                    .activity_applications
+                    .get(&activity_id)
+                    .await?
+                    .ok_or(ReviewError::InvalidActivity)?;
+                if activity.status != ReviewStatus::Pending {
+                    return Err(ReviewError::NotPending);
+                }
+                withdraw_review(
+                    &mut activity.reviewers,
+                    &mut activity.vote_history,
+                    &mut activity.approved,
+                    &mut activity.rejected,
+                    reviewer,
+                )?;
+                let host = activity.activity_host;
+                self.activity_applications.insert(&activity_id, activity)?;
+                Ok(host)
+            }
+        }
+    }
+
+    /// Whether the reviewer already voted on the application before, then the vote is not
+    /// rewarded again
+    pub(crate) async fn revoted(
+        &self,
+        object_type: ObjectType,
+        object_id: String,
+        reviewer: Owner,
+    ) -> Result<bool, ReviewError> {
+        let vote_history = match object_type {
+            ObjectType::Content => self
+                .content_applications
+                .get(&object_id)
+                .await?
+                .map(|content| content.vote_history),
+            ObjectType::Asset => self
+                .asset_applications
+                .get(&object_id)
+                .await?
+                .map(|asset| asset.vote_history),
+            ObjectType::Reviewer => self
+                .reviewer_applications
+                .get(&reviewer_object_id(&object_id)?)
+                .await?
+                .map(|application| application.vote_history),
+            ObjectType::Activity => self
+                .activity_applications
+                .get(&activity_object_id(&object_id)?)
+                .await?
+                .map(|activity| activity.vote_history),
+        };
+        Ok(vote_history
+            .unwrap_or_default()
+            .iter()
+            .any(|review| review.reviewer == reviewer))
+    }
+
+    pub(crate) fn appeal_threshold(&self, threshold: u16, approved: bool, appealing: bool) -> u16 {
+        if !appealing {
+            return threshold;
+        }
+        let appeal_threshold = if approved {
+            *self.appeal_approved_threshold.get()
+        } else {
+            *self.appeal_rejected_threshold.get()
+        };
+        appeal_threshold.unwrap_or(threshold)
+    }
+
+    /// Check the vote could be applied without changing anything
+    pub(crate) async fn validate_vote(
+        &self,
+        reviewer: Owner,
+        object_type: ObjectType,
+        object_id: String,
+        scores: &[RubricScore],
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        match object_type {
+            ObjectType::Content => {
+                self.validate_content_review(reviewer, object_id, now)
+                    .await?;
+                validate_scores(self.content_rubric.get(), scores)
+            }
+            ObjectType::Asset => {
+                self.validate_asset_review(reviewer, object_id, now).await?;
+                validate_scores(self.asset_rubric.get(), scores)
+            }
+            ObjectType::Reviewer => {
+                self.validate_reviewer_review(reviewer, reviewer_object_id(&object_id)?, now)
+                    .await?;
+                validate_scores(self.reviewer_rubric.get(), scores)
+            }
+            ObjectType::Activity => {
+                self.validate_activity_review(reviewer, activity_object_id(&object_id)?, now)
+                    .await?;
+                validate_scores(self.activity_rubric.get(), scores)
+            }
+        }
+    }
+
+    /// Comments use their own thresholds if configured
+    pub(crate) fn content_threshold(&self, comment: bool, approved: bool) -> u16 {
+        let (threshold, comment_threshold) = if approved {
+            (
+                *self.content_approved_threshold.get(),
+                *self.comment_approved_threshold.get(),
+            )
+        } else {
+            (
+                *self.content_rejected_threshold.get(),
+                *self.comment_rejected_threshold.get(),
+            )
+        };
+        if comment {
+            return comment_threshold.unwrap_or(threshold);
+        }
+        threshold
+    }
+
+    /// Comment skips review if moderation is off or the author's comment history reaches fast
+    /// path
+    pub(crate) async fn comment_needs_review(&self, author: Owner) -> Result<bool, ReviewError> {
+        if *self.comment_moderation.get() == CommentModeration::PassThrough {
+            return Ok(false);
+        }
+        let level = match *self.comment_fast_path_reputation.get() {
+            Some(level) => level,
+            None => return Ok(true),
+        };
+        Ok(!fast_path_comment(
+            self.comment_reputations.get(&author).await?,
+            level,
+        ))
+    }
+
+    async fn record_comment(
+        &mut self,
+        content: &Content,
+        approved: bool,
+    ) -> Result<(), ReviewError> {
+        if content.comment_to_cid.is_none() {
+            return Ok(());
+        }
+        let mut reputation = self
+            .comment_reputations
+            .get(&content.author)
+            .await?
+            .unwrap_or(Reputation::new(content.author));
+        reputation.record(approved);
+        self.comment_reputations
+            .insert(&content.author, reputation)?;
+        Ok(())
+    }
+
+    /// Reopen a rejected application, original voters could not review it again
+    pub(crate) async fn appeal(
+        &mut self,
+        owner: Owner,
+        object_type: ObjectType,
+        id: String,
+        argument: String,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        match object_type {
+            ObjectType::Content => {
+                let mut content = self
+                    .content_applications
+                    .get(&id)
+                    .await?
+                    .ok_or(ReviewError::InvalidContent)?;
+                if content.author != owner {
+                    return Err(ReviewError::InvalidUser);
+                }
+                if content.status != ReviewStatus::Rejected || content.appeal.is_some() {
+                    return Err(ReviewError::InvalidAppeal);
+                }
+                self.validate_appeal_reviewers(
+                    &content.excluded_reviewers,
+                    &content.reviewers,
+                    now,
+                )
+                .await?;
+                content.appeal = Some(Appeal {
+                    argument,
+                    original_reviewers: content.reviewers.clone(),
+                    original_approved: content.approved,
+                    original_rejected: content.rejected,
+                    created_at: now,
+                });
+                content
+                    .excluded_reviewers
+                    .extend(content.reviewers.drain().map(|(reviewer, _)| reviewer));
+                content.rubric_scores = Vec::new();
+                content.average_score = None;
+                content.approved = 0;
+                content.rejected = 0;
+                content.status = ReviewStatus::Pending;
+                content.assignees = self
+                    .assign_reviewers(
+                        ObjectType::Content,
+                        id.clone(),
+                        content.submitted_height,
+                        &content.excluded_reviewers,
+                        &[],
+                        now,
+                    )
+                    .await?;
+                self.content_applications.insert(&id, content)?;
+            }
+            ObjectType::Asset => {
+                let mut asset = self
+                    .asset_applications
+                    .get(&id)
+                    .await?
+                    .ok_or(ReviewError::InvalidContent)?;
+                if asset.author != owner {
+                    return Err(ReviewError::InvalidUser);
+                }
+                if asset.status != ReviewStatus::Rejected || asset.appeal.is_some() {
+                    return Err(ReviewError::InvalidAppeal);
+                }
+                self.validate_appeal_reviewers(&asset.excluded_reviewers, &asset.reviewers, now)
+                    .await?;
+                asset.appeal = Some(Appeal {
+                    argument,
+                    original_reviewers: asset.reviewers.clone(),
+                    original_approved: asset.approved,
+                    original_rejected: asset.rejected,
+                    created_at: now,
+                });
+                asset
+                    .excluded_reviewers
+                    .extend(asset.reviewers.drain().map(|(reviewer, _)| reviewer));
+                asset.rubric_scores = Vec::new();
+                asset.average_score = None;
+                asset.approved = 0;
+                asset.rejected = 0;
+                asset.status = ReviewStatus::Pending;
+                asset.assignees = self
+                    .assign_reviewers(
+                        ObjectType::Asset,
+                        id.clone(),
+                        asset.submitted_height,
+                        &asset.excluded_reviewers,
+                        &[],
+                        now,
+                    )
+                    .await?;
+                self.asset_applications.insert(&id, asset)?;
+            }
+            ObjectType::Activity => {
+                let activity_id = activity_object_id(&id)?;
+                let mut activity = self
+                    .activity_applications
+                    .get(&activity_id)
+                    .await?
+                    .ok_or(ReviewError::InvalidActivity)?;
+                if activity.activity_host != owner {
+                    return Err(ReviewError::InvalidUser);
+                }
+                if activity.status != ReviewStatus::Rejected || activity.appeal.is_some() {
+                    return Err(ReviewError::InvalidAppeal);
+                }
+                self.validate_appeal_reviewers(
+                    &activity.excluded_reviewers,
+                    &activity.reviewers,
+                    now,
+                )
+                .await?;
+                activity.appeal = Some(Appeal {
+                    argument,
+                    original_reviewers: activity.reviewers.clone(),
+                    original_approved: activity.approved,
+                    original_rejected: activity.rejected,
+                    created_at: now,
+                });
+                activity
+                    .excluded_reviewers
+                    .extend(activity.reviewers.drain().map(|(reviewer, _)| reviewer));
+                activity.approved = 0;
+                activity.rejected = 0;
+                activity.status = ReviewStatus::Pending;
+                activity.assignees = self
+                    .assign_reviewers(
+                        ObjectType::Activity,
+                        id.clone(),
+                        activity.submitted_height,
+                        &activity.excluded_reviewers,
+                        &[],
+                        now,
+                    )
+                    .await?;
+                self.activity_applications.insert(&activity_id, activity)?;
+            }
+            ObjectType::Reviewer => return Err(ReviewError::InvalidAppeal),
+        }
+        Ok(())
+    }
+
+    /// Reviewers of the original decision can't review the appeal, so someone else must be left
+    async fn validate_appeal_reviewers(
+        &self,
+        excluded: &[Owner],
+        original_reviewers: &HashMap<Owner, _Review>,
+        now: Timestamp,
+    ) -> Result<(), ReviewError> {
+        let mut excluded = excluded.to_vec();
+        excluded.extend(original_reviewers.keys().copied());
+        if self.eligible_reviewers(&excluded, now).await?.is_empty() {
+            return Err(ReviewError::NoAppealReviewer);
+        }
+        Ok(())
+    }
+
+    /// Reviewers counted by decision, assignees in assignment mode otherwise all eligible reviewers
+    pub(crate) async fn decision_reviewers(
+        &self,
+        assignees: &[Assignment],
+        excluded: &[Owner],
+        now: Timestamp,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        if assignees.is_empty() {
+            return self.eligible_reviewers(excluded, now).await;
+        }
+        Ok(assignees
+            .iter()
+            .filter(|assignment| !assignment.timed_out)
+            .map(|assignment| assignment.reviewer)
+            .collect())
+    }
+
+    /// Append picked reviewers to assignees until the assigned reviewer number is reached, a
+    /// reviewer assigned before is never picked again. Nobody is picked in open voting mode.
+    pub(crate) async fn assign_reviewers(
+        &self,
+        object_type: ObjectType,
+        object_id: String,
+        height: BlockHeight,
+        excluded: &[Owner],
+        assignees: &[Assignment],
+        now: Timestamp,
+    ) -> Result<Vec<Assignment>, ReviewError> {
+        let number = match *self.assigned_reviewer_number.get() {
+            Some(number) => number,
+            None => return Ok(Vec::new()),
+        };
+        let active = assignees
+            .iter()
+            .filter(|assignment| !assignment.timed_out)
+            .count();
+        let mut candidates = Vec::new();
+        for reviewer in self.eligible_reviewers(excluded, now).await? {
+            if assignees
+                .iter()
+                .any(|assignment| assignment.reviewer == reviewer)
+            {
+                continue;
+            }
+            let rank = CryptoHash::new(&AssignmentSeed {
+                object_type,
+                object_id: object_id.clone(),
+                height,
+                round: assignees.len() as u32,
+                reviewer,
+            });
+            candidates.push((rank, reviewer));
+        }
+        candidates.sort();
+        let mut assigned = assignees.to_vec();
+        assigned.extend(
+            candidates
+                .into_iter()
+                .take((number as usize).saturating_sub(active))
+                .map(|(_, reviewer)| Assignment {
+                    reviewer,
+                    assigned_at: now,
+                    timed_out: false,
+                }),
+        );
+        Ok(assigned)
+    }
+
+    /// Replace assignees who didn't vote within deadline, only called on creation chain
+    pub(crate) async fn reassign_reviewers(
+        &mut self,
+        now: Timestamp,
+    ) -> Result<Vec<Reassignment>, ReviewError> {
+        let deadline_ms = *self.assignment_deadline_ms.get();
+        let mut reassignments = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            let content = self.content_applications.get(&cid).await?.unwrap();
+            if content.status != ReviewStatus::Pending {
+                continue;
+            }
+            let mut assignees = content.assignees.clone();
+            let timed_out =
+                time_out_assignees(&mut assignees, &content.reviewers, deadline_ms, now);
+            if timed_out.is_empty() {
+                continue;
+            }
+            let assignees = self
+                .assign_reviewers(
+                    ObjectType::Content,
+                    cid.clone(),
+                    content.submitted_height,
+                    &content.excluded_reviewers,
+                    &assignees,
+                    now,
+                )
+                .await?;
+            reassignments.push(Reassignment {
+                object_type: ObjectType::Content,
+                object_id: cid,
+                timed_out,
+                assigned: assignees[content.assignees.len()..].to_vec(),
+            });
+        }
+        for cid in self.asset_applications.indices().await? {
+            let asset = self.asset_applications.get(&cid).await?.unwrap();
+            if asset.status != ReviewStatus::Pending {
+                continue;
+            }
+            let mut assignees = asset.assignees.clone();
+            let timed_out = time_out_assignees(&mut assignees, &asset.reviewers, deadline_ms, now);
+            if timed_out.is_empty() {
+                continue;
+            }
+            let assignees = self
+                .assign_reviewers(
+                    ObjectType::Asset,
+                    cid.clone(),
+                    asset.submitted_height,
+                    &asset.excluded_reviewers,
+                    &assignees,
+                    now,
+                )
+                .await?;
+            reassignments.push(Reassignment {
+                object_type: ObjectType::Asset,
+                object_id: cid,
+                timed_out,
+                assigned: assignees[asset.assignees.len()..].to_vec(),
+            });
+        }
+        for candidate in self.reviewer_applications.indices().await? {
+            let application = self.reviewer_applications.get(&candidate).await?.unwrap();
+            if application.status != ReviewStatus::Pending {
+                continue;
+            }
+            let mut assignees = application.assignees.clone();
+            let timed_out =
+                time_out_assignees(&mut assignees, &application.reviewers, deadline_ms, now);
+            if timed_out.is_empty() {
+                continue;
+            }
+            let assignees = self
+                .assign_reviewers(
+                    ObjectType::Reviewer,
+                    candidate.to_string(),
+                    application.submitted_height,
+                    &application.excluded_reviewers,
+                    &assignees,
+                    now,
+                )
+                .await?;
+            reassignments.push(Reassignment {
+                object_type: ObjectType::Reviewer,
+                object_id: candidate.to_string(),
+                timed_out,
+                assigned: assignees[application.assignees.len()..].to_vec(),
+            });
+        }
+        for activity_id in self.activity_applications.indices().await? {
+            let activity = self.activity_applications.get(&activity_id).await?.unwrap();
+            if activity.status != ReviewStatus::Pending {
+                continue;
+            }
+            let mut assignees = activity.assignees.clone();
+            let timed_out =
+                time_out_assignees(&mut assignees, &activity.reviewers, deadline_ms, now);
+            if timed_out.is_empty() {
+                continue;
+            }
+            let assignees = self
+                .assign_reviewers(
+                    ObjectType::Activity,
+                    activity_id.to_string(),
+                    activity.submitted_height,
+                    &activity.excluded_reviewers,
+                    &assignees,
+                    now,
+                )
+                .await?;
+            reassignments.push(Reassignment {
+                object_type: ObjectType::Activity,
+                object_id: activity_id.to_string(),
+                timed_out,
+                assigned: assignees[activity.assignees.len()..].to_vec(),
+            });
+        }
+        for reassignment in &reassignments {
+            self.apply_reassignment(reassignment).await?;
+        }
+        Ok(reassignments)
+    }
+
+    /// Apply the reassignment decided by creation chain, applications which are not pending
+    /// any more are left unchanged
+    pub(crate) async fn apply_reassignment(
+        &mut self,
+        reassignment: &Reassignment,
+    ) -> Result<(), ReviewError> {
+        let object_id = reassignment.object_id.clone();
+        match reassignment.object_type {
+            ObjectType::Content => {
+                let Some(mut content) = self.content_applications.get(&object_id).await? else {
+                    return Ok(());
+                };
+                if content.status == ReviewStatus::Pending {
+                    reassign_assignees(&mut content.assignees, reassignment);
+                    self.content_applications.insert(&object_id, content)?;
+                }
+            }
+            ObjectType::Asset => {
+                let Some(mut asset) = self.asset_applications.get(&object_id).await? else {
+                    return Ok(());
+                };
+                if asset.status == ReviewStatus::Pending {
+                    reassign_assignees(&mut asset.assignees, reassignment);
+                    self.asset_applications.insert(&object_id, asset)?;
+                }
+            }
+            ObjectType::Reviewer => {
+                let candidate = reviewer_object_id(&object_id)?;
+                let Some(mut application) = self.reviewer_applications.get(&candidate).await?
+                else {
+                    return Ok(());
+                };
+                if application.status == ReviewStatus::Pending {
+                    reassign_assignees(&mut application.assignees, reassignment);
+                    self.reviewer_applications.insert(&candidate, application)?;
+                }
+            }
+            ObjectType::Activity => {
+                let activity_id = activity_object_id(&object_id)?;
+                let Some(mut activity) = self.activity_applications.get(&activity_id).await? else {
+                    return Ok(());
+                };
+                if activity.status == ReviewStatus::Pending {
+                    reassign_assignees(&mut activity.assignees, reassignment);
+                    self.activity_applications.insert(&activity_id, activity)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pending applications assigned to the reviewer which are not voted yet
+    pub(crate) async fn assigned_queue(
+        &self,
+        reviewer: Owner,
+    ) -> Result<Vec<AssignedApplication>, ReviewError> {
+        let mut queue = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            let content = self.content_applications.get(&cid).await?.unwrap();
+            if content.status != ReviewStatus::Pending {
+                continue;
+            }
+            queue.extend(assigned_application(
+                reviewer,
+                ObjectType::Content,
+                cid,
+                &content.assignees,
+                &content.reviewers,
+            ));
+        }
+        for cid in self.asset_applications.indices().await? {
+            let asset = self.asset_applications.get(&cid).await?.unwrap();
+            if asset.status != ReviewStatus::Pending {
+                continue;
+            }
+            queue.extend(assigned_application(
+                reviewer,
+                ObjectType::Asset,
+                cid,
+                &asset.assignees,
+                &asset.reviewers,
+            ));
+        }
+        for candidate in self.reviewer_applications.indices().await? {
+            let application = self.reviewer_applications.get(&candidate).await?.unwrap();
+            if application.status != ReviewStatus::Pending {
+                continue;
+            }
+            queue.extend(assigned_application(
+                reviewer,
+                ObjectType::Reviewer,
+                candidate.to_string(),
+                &application.assignees,
+                &application.reviewers,
+            ));
+        }
+        for activity_id in self.activity_applications.indices().await? {
+            let activity = self.activity_applications.get(&activity_id).await?.unwrap();
+            if activity.status != ReviewStatus::Pending {
+                continue;
+            }
+            queue.extend(assigned_application(
+                reviewer,
+                ObjectType::Activity,
+                activity_id.to_string(),
+                &activity.assignees,
+                &activity.reviewers,
+            ));
+        }
+        queue.sort_by_key(|application| application.assigned_at);
+        Ok(queue)
+    }
+
+    /// Active assignees of the application
+    pub(crate) async fn assignees(
+        &self,
+        object_type: ObjectType,
+        object_id: String,
+    ) -> Result<Vec<Owner>, ReviewError> {
+        let assignees = match object_type {
+            ObjectType::Content => self
+                .content_applications
+                .get(&object_id)
+                .await?
+                .map(|content| content.assignees),
+            ObjectType::Asset => self
+                .asset_applications
+                .get(&object_id)
+                .await?
+                .map(|asset| asset.assignees),
+            ObjectType::Reviewer => self
+                .reviewer_applications
+                .get(&reviewer_object_id(&object_id)?)
+                .await?
+                .map(|application| application.assignees),
+            ObjectType::Activity => self
+                .activity_applications
+                .get(&activity_object_id(&object_id)?)
+                .await?
+                .map(|activity| activity.assignees),
+        };
+        Ok(assignees
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|assignment| !assignment.timed_out)
+            .map(|assignment| assignment.reviewer)
+            .collect())
+    }
+
+    pub(crate) async fn pending_contents(
+        &self,
+        filter: PendingFilter,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Content>, ReviewError> {
+        let mut contents = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            let content = self.content_applications.get(&cid).await?.unwrap();
+            if pending_matched(
+                &filter,
+                &content.status,
+                content.author,
+                content.created_at,
+                &content.reviewers,
+            ) {
+                contents.push((cid, content));
+            }
+        }
+        let (items, next_cursor) = paginate(contents, after, first)?;
+        Ok(Page { items, next_cursor })
+    }
+
+    pub(crate) async fn pending_assets(
+        &self,
+        filter: PendingFilter,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Asset>, ReviewError> {
+        let mut assets = Vec::new();
+        for cid in self.asset_applications.indices().await? {
+            let asset = self.asset_applications.get(&cid).await?.unwrap();
+            if pending_matched(
+                &filter,
+                &asset.status,
+                asset.author,
+                asset.created_at,
+                &asset.reviewers,
+            ) {
+                assets.push((cid, asset));
+            }
+        }
+        let (items, next_cursor) = paginate(assets, after, first)?;
+        Ok(Page { items, next_cursor })
+    }
+
+    pub(crate) async fn pending_reviewers(
+        &self,
+        filter: PendingFilter,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Reviewer>, ReviewError> {
+        let mut applications = Vec::new();
+        for candidate in self.reviewer_applications.indices().await? {
+            let application = self.reviewer_applications.get(&candidate).await?.unwrap();
+            if pending_matched(
+                &filter,
+                &application.status,
+                application.reviewer,
+                application.created_at,
+                &application.reviewers,
+            ) {
+                applications.push((candidate, application));
+            }
+        }
+        let (items, next_cursor) = paginate(applications, after, first)?;
+        Ok(Page { items, next_cursor })
+    }
+
+    pub(crate) async fn pending_activities(
+        &self,
+        filter: PendingFilter,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> Result<Page<Activity>, ReviewError> {
+        let mut activities = Vec::new();
+        for activity_id in self.activity_applications.indices().await? {
+            let activity = self.activity_applications.get(&activity_id).await?.unwrap();
+            if pending_matched(
+                &filter,
+                &activity.status,
+                activity.activity_host,
+                activity.created_at,
+                &activity.reviewers,
+            ) {
+                activities.push((activity_id, activity));
+            }
+        }
+        let (items, next_cursor) = paginate(activities, after, first)?;
+        Ok(Page { items, next_cursor })
+    }
+
+    /// Current votes of the reviewer on all applications, latest first
+    pub(crate) async fn review_history(
+        &self,
+        reviewer: Owner,
+    ) -> Result<Vec<ReviewRecord>, ReviewError> {
+        let mut records = Vec::new();
+        for cid in self.content_applications.indices().await? {
+            let content = self.content_applications.get(&cid).await?.unwrap();
+            records.extend(review_records(
+                reviewer,
+                ObjectType::Content,
+                cid,
+                &content.status,
+                &content.reviewers,
+            ));
+        }
+        for cid in self.asset_applications.indices().await? {
+            let asset = self.asset_applications.get(&cid).await?.unwrap();
+            records.extend(review_records(
+                reviewer,
+                ObjectType::Asset,
+                cid,
+                &asset.status,
+                &asset.reviewers,
+            ));
+        }
+        for candidate in self.reviewer_applications.indices().await? {
+            let application = self.reviewer_applications.get(&candidate).await?.unwrap();
+            records.extend(review_records(
+                reviewer,
+                ObjectType::Reviewer,
+                candidate.to_string(),
+                &application.status,
+                &application.reviewers,
+            ));
+        }
+        // Approved candidates keep their application votes
+        for candidate in self.reviewers.indices().await? {
+            let _reviewer = self.reviewers.get(&candidate).await?.unwrap();
+            records.extend(review_records(
+                reviewer,
+                ObjectType::Reviewer,
+                candidate.to_string(),
+                &_reviewer.status,
+                &_reviewer.reviewers,
+            ));
+        }
+        for activity_id in self.activity_applications.indices().await? {
+            let activity = self.activity_applications.get(&activity_id).await?.unwrap();
+            records.extend(review_records(
+                reviewer,
+                ObjectType::Activity,
+                activity_id.to_string(),
+                &activity.status,
+                &activity.reviewers,
+            ));
+        }
+        records.sort_by(|a, b| b.review.created_at.cmp(&a.review.created_at));
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, str::FromStr};
+
+    use linera_sdk::base::{Owner, Timestamp};
+    use review::{
+        Assignment, DecisionMode, DecisionPolicy, ObjectType, Reassignment, Reputation, Review,
+        RubricScore,
+    };
+
+    use super::{
+        aggregate_scores, count_decided, fast_path_comment, paginate, reassign_assignees,
+        time_out_assignees, validate_scores, weight_decided, DEFAULT_PAGE_SIZE,
+    };
+
+    fn owner(index: u8) -> Owner {
+        Owner::from_str(&format!("{:064x}", index)).unwrap()
+    }
+
+    #[test]
+    fn count_decision() {
+        assert!(count_decided(3, 3, 10));
+        assert!(!count_decided(2, 3, 10));
+        // Fewer eligible reviewers than threshold decide unanimously
+        assert!(count_decided(2, 3, 2));
+        assert!(!count_decided(0, 3, 0));
+    }
+
+    #[test]
+    fn weight_decision_exceeds_percent() {
+        assert!(weight_decided(51, 100, 50));
+        assert!(!weight_decided(50, 100, 50));
+        assert!(weight_decided(100, 100, 99));
+        assert!(!weight_decided(100, 100, 100));
+    }
+
+    #[test]
+    fn decision_policy_percents() {
+        let policy = |mode, approved_percent, rejected_percent| DecisionPolicy {
+            mode,
+            approved_percent,
+            rejected_percent,
+        };
+        assert!(policy(DecisionMode::Count, 0, 0).validate().is_ok());
+        assert!(policy(DecisionMode::Weighted, 50, 99).validate().is_ok());
+        assert!(policy(DecisionMode::Weighted, 0, 50).validate().is_err());
+        assert!(policy(DecisionMode::Weighted, 50, 100).validate().is_err());
+    }
+
+    #[test]
+    fn highest_valid_percent_decides_unanimous_weight() {
+        let policy = DecisionPolicy {
+            mode: DecisionMode::Weighted,
+            approved_percent: 99,
+            rejected_percent: 99,
+        };
+        assert!(policy.validate().is_ok());
+        assert!(weight_decided(100, 100, policy.approved_percent));
+        assert!(!weight_decided(99, 100, policy.approved_percent));
+    }
+
+    #[test]
+    fn subscribers_follow_reassignment() {
+        let assignment = |index, assigned_at| Assignment {
+            reviewer: owner(index),
+            assigned_at: Timestamp::from(assigned_at),
+            timed_out: false,
+        };
+        let mut assignees = vec![assignment(1, 0), assignment(2, 5_000_000)];
+        let timed_out = time_out_assignees(
+            &mut assignees.clone(),
+            &HashMap::default(),
+            Some(3_000),
+            Timestamp::from(4_000_000),
+        );
+        assert_eq!(timed_out, vec![owner(1)]);
+
+        reassign_assignees(
+            &mut assignees,
+            &Reassignment {
+                object_type: ObjectType::Content,
+                object_id: "cid".to_string(),
+                timed_out,
+                assigned: vec![assignment(3, 4_000_000)],
+            },
+        );
+        assert!(assignees[0].timed_out);
+        assert!(!assignees[1].timed_out);
+        assert_eq!(assignees[2].reviewer, owner(3));
+    }
+
+    fn items(keys: &[u64]) -> Vec<(u64, u64)> {
+        keys.iter().map(|key| (*key, key * 10)).collect()
+    }
+
+    #[test]
+    fn pages_in_key_order() {
+        let (page, cursor) = paginate(items(&[3, 1, 10, 2]), None, Some(2)).unwrap();
+        assert_eq!(page, vec![10, 20]);
+        let (page, cursor) = paginate(items(&[3, 1, 10, 2]), cursor, Some(2)).unwrap();
+        assert_eq!(page, vec![30, 100]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn resumes_after_removed_key() {
+        let (_, cursor) = paginate(items(&[1, 2, 3, 4]), None, Some(2)).unwrap();
+        // Item 2 is gone before the next page is fetched
+        let (page, cursor) = paginate(items(&[1, 3, 4]), cursor, Some(2)).unwrap();
+        assert_eq!(page, vec![30, 40]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn last_full_page_has_no_cursor() {
+        let (page, cursor) = paginate(items(&[1, 2]), None, Some(2)).unwrap();
+        assert_eq!(page, vec![10, 20]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn default_page_size() {
+        let keys = (0..25).collect::<Vec<_>>();
+        let (page, cursor) = paginate(items(&keys), None, None).unwrap();
+        assert_eq!(page.len(), DEFAULT_PAGE_SIZE);
+        assert_eq!(cursor, Some("19".to_string()));
+    }
+
+    #[test]
+    fn composite_keys() {
+        let items = vec![(("b".to_string(), 1u16), 1), (("a".to_string(), 2u16), 2)];
+        let (page, cursor) = paginate(items.clone(), None, Some(1)).unwrap();
+        assert_eq!(page, vec![2]);
+        let (page, _) = paginate(items, cursor, Some(1)).unwrap();
+        assert_eq!(page, vec![1]);
+    }
+
+    #[test]
+    fn invalid_cursor() {
+        assert!(paginate(items(&[1]), Some("x".to_string()), None).is_err());
+    }
+
+    fn rubric() -> Vec<String> {
+        vec!["accuracy".to_string(), "clarity".to_string()]
+    }
+
+    fn scores(accuracy: u8, clarity: u8) -> Vec<RubricScore> {
+        vec![
+            RubricScore {
+                criterion: "accuracy".to_string(),
+                score: accuracy,
+            },
+            RubricScore {
+                criterion: "clarity".to_string(),
+                score: clarity,
+            },
+        ]
+    }
+
+    #[test]
+    fn unscored_vote_is_valid() {
+        assert!(validate_scores(&rubric(), &[]).is_ok());
+        assert!(validate_scores(&[], &[]).is_ok());
+    }
+
+    #[test]
+    fn scored_vote_covers_rubric_once() {
+        assert!(validate_scores(&rubric(), &scores(1, 5)).is_ok());
+        assert!(validate_scores(&rubric(), &scores(1, 5)[..1]).is_err());
+        assert!(validate_scores(&[], &scores(1, 5)).is_err());
+        let mut duplicated = scores(3, 3);
+        duplicated[1].criterion = "accuracy".to_string();
+        assert!(validate_scores(&rubric(), &duplicated).is_err());
+    }
+
+    #[test]
+    fn scores_in_range() {
+        assert!(validate_scores(&rubric(), &scores(0, 3)).is_err());
+        assert!(validate_scores(&rubric(), &scores(3, 6)).is_err());
+    }
+
+    #[test]
+    fn aggregate_skips_unscored_votes() {
+        let mut reviews = HashMap::new();
+        for (index, scores) in [scores(4, 5), scores(5, 2), Vec::new()]
+            .into_iter()
+            .enumerate()
+        {
+            let reviewer = owner(index as u8);
+            reviews.insert(
+                reviewer,
+                Review {
+                    reviewer,
+                    approved: true,
+                    reason: String::new(),
+                    scores,
+                    created_at: Timestamp::from(0),
+                },
+            );
+        }
+        let (averages, average) = aggregate_scores(&rubric(), &reviews);
+        assert_eq!(averages.len(), 2);
+        assert_eq!(averages[0].average, 450);
+        assert_eq!(averages[1].average, 350);
+        assert_eq!(average, Some(400));
+    }
+
+    #[test]
+    fn aggregate_without_scores() {
+        let (averages, average) = aggregate_scores(&rubric(), &HashMap::new());
+        assert!(averages.is_empty());
+        assert_eq!(average, None);
+    }
+
+    #[test]
+    fn comment_fast_path_follows_comment_history() {
+        assert!(!fast_path_comment(None, 50));
+        let mut history = Reputation::new(owner(1));
+        history.record(true);
+        history.record(true);
+        assert!(fast_path_comment(Some(history.clone()), 70));
+        history.record(false);
+        history.record(false);
+        assert!(!fast_path_comment(Some(history), 70));
+    }
 }